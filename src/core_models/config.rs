@@ -0,0 +1,501 @@
+use std::fmt;
+use toml::{Table, Value};
+use crate::core_models::constant_optimization::{ConstantOptimizer, Method, Refinement};
+use crate::core_models::dannn::{Complexity, NodeSelectionTable};
+use crate::core_models::fitness::Metric;
+use crate::core_models::novelty::NoveltySettings;
+use crate::core_models::profile::Profile;
+use crate::core_models::speciation::SpeciationSettings;
+use crate::core_models::trainer::{ComplexityWeights, Selection, TrainerSettings};
+
+/*
+A training run described in a TOML file. Every key is optional, leaving one out keeps the default:
+
+    [training]
+    generations = 100
+    metric = "mean_squared_error"     # or mean_absolute_error, root_mean_squared_error, log_loss, accuracy, f1_score, huber, edit_distance
+    huber_delta = 1.0
+    parsimony = 0.001
+    targets = ["y"]
+    validation_fraction = 0.2
+    objectives = ["loss", "node_count"]   # more than one objective trains with NSGA-II
+    mode = "novelty"                  # or standard, novelty search selects on the outputs of networks as well as their loss
+    checkpoint_interval = 10
+
+    [trainer]
+    population_size = 200
+    hidden_layer_count = 2
+    max_nodes_per_layer = 4
+    constant_range = 10.0
+    mutation_rate = 0.8
+    tournament_size = 3
+    elitism = 1
+    seed = 7
+    selection = "lexicographic"       # or fitness, penalized
+    lexicographic_tolerance = 0.01    # only with lexicographic selection
+    penalty = { node = 0.01, connection = 0.0, depth = 0.0, effective_node = 0.0 }   # only with penalized selection
+
+    [trainer.speciation]              # speciation is on when the table is present
+    threshold = 1.0
+    stagnation_limit = 15
+    node_type = 1.0
+    connection = 1.0
+    constant = 0.4
+
+    [trainer.novelty]                 # only with the novelty mode
+    nearest_neighbors = 15
+    archive_threshold = 1.0
+    max_archive_size = 1000
+    novelty_weight = 0.5
+
+    [trainer.refinement]              # refinement is on when the table is present
+    method = "nelder_mead"            # or hill_climbing, cma_es
+    step_size = 1.0
+    evaluations = 200
+    network_count = 3
+
+    [nodes]
+    profile = "symbolic regression"             # a preset or a profile file to start from, see the profile module
+    families = ["logic", "simple_arithmetic"]   # every family is enabled when none of profile, families or enable is given
+    enable = [401]
+    disable = [204]
+    prevalence = { 201 = 0.5, 113 = 0.1 }
+
+Unknown keys and bad values are errors, and every error names the key it is about (for example
+"nodes.prevalence.201: must be between 0 and 1"), so a typo never silently falls back to a default. Keys that the
+chosen mode or selection would ignore are errors too.
+The input and output counts are not part of the config, they come from the dataset.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    pub key: String, //Dotted path of the offending key, empty when the file could not be read or parsed at all
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.key.is_empty() {
+            return write!(formatter, "{}", self.message);
+        }
+        write!(formatter, "{}: {}", self.key, self.message)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    Loss,
+    NodeCount,
+    ConnectionCount,
+    Depth,
+    EffectiveNodeCount,
+}
+
+impl Objective {
+    pub fn measure(&self, loss: f64, complexity: &Complexity) -> f64 {
+        match self {
+            Objective::Loss => loss,
+            Objective::NodeCount => complexity.node_count as f64,
+            Objective::ConnectionCount => complexity.connection_count as f64,
+            Objective::Depth => complexity.depth as f64,
+            Objective::EffectiveNodeCount => complexity.effective_node_count as f64,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Standard, //Selects on loss, with NSGA-II when there is more than one objective
+    Novelty, //Selects on the novelty of the outputs mixed with the loss (see the novelty module)
+}
+
+#[derive(Clone, Debug)]
+pub struct TrainingConfig {
+    pub settings: TrainerSettings,
+    pub node_selection_table: NodeSelectionTable,
+    pub generations: usize,
+    pub metric: Metric,
+    pub parsimony: f64,
+    pub targets: Option<Vec<String>>, //None keeps the datasets default targets
+    pub validation_fraction: f64,
+    pub objectives: Vec<Objective>,
+    pub mode: Mode,
+    pub checkpoint_interval: usize,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> TrainingConfig {
+        let mut node_selection_table = NodeSelectionTable::new();
+        node_selection_table.enable_all();
+        TrainingConfig {
+            settings: TrainerSettings::default(),
+            node_selection_table,
+            generations: 100,
+            metric: Metric::MeanSquaredError,
+            parsimony: 0.0,
+            targets: None,
+            validation_fraction: 0.0,
+            objectives: vec![Objective::Loss],
+            mode: Mode::Standard,
+            checkpoint_interval: 10,
+        }
+    }
+}
+
+struct Section<'a> {
+    path: String,
+    table: &'a Table,
+}
+
+impl<'a> Section<'a> {
+    fn key_path(&self, key: &str) -> String {
+        if self.path.is_empty() || key.is_empty() {
+            return format!("{}{}", self.path, key);
+        }
+        format!("{}.{}", self.path, key)
+    }
+
+    fn error(&self, key: &str, message: &str) -> ConfigError {
+        ConfigError { key: self.key_path(key), message: message.to_string() }
+    }
+
+    fn allow_keys(&self, keys: &[&str]) -> Result<(), ConfigError> {
+        match self.table.keys().find(|key| !keys.contains(&key.as_str())) {
+            Some(key) => Err(self.error(key, &format!("unknown key, expected one of {}", keys.join(", ")))),
+            None => Ok(()),
+        }
+    }
+
+    fn section(&self, key: &str) -> Result<Option<Section<'a>>, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::Table(table)) => Ok(Some(Section { path: self.key_path(key), table })),
+            Some(_) => Err(self.error(key, "must be a table")),
+            None => Ok(None),
+        }
+    }
+
+    fn number(&self, key: &str, default: f64) -> Result<f64, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::Float(number)) if number.is_finite() => Ok(*number),
+            Some(Value::Integer(number)) => Ok(*number as f64),
+            Some(_) => Err(self.error(key, "must be a finite number")),
+            None => Ok(default),
+        }
+    }
+
+    fn ranged_number(&self, key: &str, default: f64, minimum: f64, maximum: f64) -> Result<f64, ConfigError> {
+        let number = self.number(key, default)?;
+        if number < minimum || number > maximum {
+            return Err(self.error(key, &format!("must be between {} and {}", minimum, maximum)));
+        }
+        Ok(number)
+    }
+
+    fn count(&self, key: &str, default: usize, minimum: usize) -> Result<usize, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::Integer(number)) if *number >= minimum as i64 => Ok(*number as usize),
+            Some(_) => Err(self.error(key, &format!("must be a whole number of at least {}", minimum))),
+            None => Ok(default),
+        }
+    }
+
+    fn text(&self, key: &str) -> Result<Option<&'a str>, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::String(text)) => Ok(Some(text.as_str())),
+            Some(_) => Err(self.error(key, "must be text")),
+            None => Ok(None),
+        }
+    }
+
+    fn text_list(&self, key: &str) -> Result<Option<Vec<&'a str>>, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::Array(items)) => items.iter().enumerate().map(|(index, item)| match item {
+                Value::String(text) => Ok(text.as_str()),
+                _ => Err(self.error(&format!("{}[{}]", key, index), "must be text")),
+            }).collect::<Result<Vec<&str>, ConfigError>>().map(Some),
+            Some(_) => Err(self.error(key, "must be a list of text")),
+            None => Ok(None),
+        }
+    }
+
+    fn id_list(&self, key: &str) -> Result<Vec<usize>, ConfigError> {
+        match self.table.get(key) {
+            Some(Value::Array(items)) => items.iter().enumerate().map(|(index, item)| match item {
+                Value::Integer(id) if *id > 0 && NodeSelectionTable::node_type_name(*id as usize).is_some() => Ok(*id as usize),
+                _ => Err(self.error(&format!("{}[{}]", key, index), "must be a known node type id")),
+            }).collect(),
+            Some(_) => Err(self.error(key, "must be a list of node type ids")),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl TrainingConfig {
+    pub fn from_toml_str(text: &str) -> Result<TrainingConfig, ConfigError> {
+        let table: Table = text.parse().map_err(|error: toml::de::Error| ConfigError { key: String::new(), message: error.to_string().trim().to_string() })?;
+        let root = Section { path: String::new(), table: &table };
+        root.allow_keys(&["training", "trainer", "nodes"])?;
+        let mut config = TrainingConfig::default();
+
+        if let Some(training) = root.section("training")? {
+            training.allow_keys(&["generations", "metric", "huber_delta", "parsimony", "targets", "validation_fraction", "objectives", "mode", "checkpoint_interval"])?;
+            config.generations = training.count("generations", config.generations, 0)?;
+            config.metric = match training.text("metric")?.unwrap_or("mean_squared_error") {
+                "mean_squared_error" => Metric::MeanSquaredError,
+                "mean_absolute_error" => Metric::MeanAbsoluteError,
+                "root_mean_squared_error" => Metric::RootMeanSquaredError,
+                "log_loss" => Metric::LogLoss,
+                "accuracy" => Metric::Accuracy,
+                "f1_score" => Metric::F1Score,
+                "huber" => Metric::Huber(training.ranged_number("huber_delta", 1.0, 0.0, f64::MAX)?),
+                "edit_distance" => Metric::EditDistance,
+                _ => return Err(training.error("metric", "unknown metric")),
+            };
+            config.parsimony = training.ranged_number("parsimony", 0.0, 0.0, f64::MAX)?;
+            config.targets = training.text_list("targets")?.map(|targets| targets.iter().map(|target| target.to_string()).collect());
+            config.validation_fraction = training.ranged_number("validation_fraction", 0.0, 0.0, 0.99)?;
+            if let Some(objectives) = training.text_list("objectives")? {
+                if objectives.is_empty() {
+                    return Err(training.error("objectives", "needs at least one objective"));
+                }
+                config.objectives = objectives.iter().enumerate().map(|(index, objective)| match *objective {
+                    "loss" => Ok(Objective::Loss),
+                    "node_count" => Ok(Objective::NodeCount),
+                    "connection_count" => Ok(Objective::ConnectionCount),
+                    "depth" => Ok(Objective::Depth),
+                    "effective_node_count" => Ok(Objective::EffectiveNodeCount),
+                    _ => Err(training.error(&format!("objectives[{}]", index), "unknown objective")),
+                }).collect::<Result<Vec<Objective>, ConfigError>>()?;
+            }
+            config.mode = match training.text("mode")?.unwrap_or("standard") {
+                "standard" => Mode::Standard,
+                "novelty" if config.objectives.len() > 1 => return Err(training.error("mode", "novelty needs a single objective")),
+                "novelty" => Mode::Novelty,
+                _ => return Err(training.error("mode", "must be standard or novelty")),
+            };
+            config.checkpoint_interval = training.count("checkpoint_interval", config.checkpoint_interval, 1)?;
+        }
+
+        if let Some(trainer) = root.section("trainer")? {
+            trainer.allow_keys(&[
+                "population_size", "hidden_layer_count", "max_nodes_per_layer", "constant_range", "mutation_rate", "tournament_size",
+                "elitism", "seed", "selection", "lexicographic_tolerance", "penalty", "speciation", "novelty", "refinement",
+            ])?;
+            let settings = &mut config.settings;
+            settings.population_size = trainer.count("population_size", settings.population_size, 1)?;
+            settings.hidden_layer_count = trainer.count("hidden_layer_count", settings.hidden_layer_count, 0)?;
+            settings.max_nodes_per_layer = trainer.count("max_nodes_per_layer", settings.max_nodes_per_layer, 1)?;
+            settings.constant_range = trainer.ranged_number("constant_range", settings.constant_range, 0.0, f64::MAX)?;
+            settings.mutation_rate = trainer.ranged_number("mutation_rate", settings.mutation_rate, 0.0, 0.99)?;
+            settings.tournament_size = trainer.count("tournament_size", settings.tournament_size, 1)?;
+            settings.elitism = trainer.count("elitism", settings.elitism, 0)?;
+            if settings.elitism > settings.population_size {
+                return Err(trainer.error("elitism", "can't be larger than population_size"));
+            }
+            settings.seed = trainer.count("seed", settings.seed as usize, 0)? as u64;
+            let selection = trainer.text("selection")?.unwrap_or("fitness");
+            if trainer.table.contains_key("lexicographic_tolerance") && selection != "lexicographic" {
+                return Err(trainer.error("lexicographic_tolerance", "is only used by lexicographic selection"));
+            }
+            if trainer.table.contains_key("penalty") && selection != "penalized" {
+                return Err(trainer.error("penalty", "is only used by penalized selection"));
+            }
+            settings.selection = match selection {
+                "fitness" => Selection::Fitness,
+                "lexicographic" => Selection::Lexicographic(trainer.ranged_number("lexicographic_tolerance", 0.0, 0.0, f64::MAX)?),
+                "penalized" => {
+                    let penalty = trainer.section("penalty")?.ok_or(trainer.error("penalty", "is needed by penalized selection"))?;
+                    penalty.allow_keys(&["node", "connection", "depth", "effective_node"])?;
+                    Selection::Penalized(ComplexityWeights {
+                        node: penalty.ranged_number("node", 0.0, 0.0, f64::MAX)?,
+                        connection: penalty.ranged_number("connection", 0.0, 0.0, f64::MAX)?,
+                        depth: penalty.ranged_number("depth", 0.0, 0.0, f64::MAX)?,
+                        effective_node: penalty.ranged_number("effective_node", 0.0, 0.0, f64::MAX)?,
+                    })
+                }
+                _ => return Err(trainer.error("selection", "must be fitness, lexicographic or penalized")),
+            };
+            if config.mode == Mode::Novelty {
+                for key in ["speciation", "refinement"] {
+                    if trainer.table.contains_key(key) {
+                        return Err(trainer.error(key, "is not used by the novelty mode"));
+                    }
+                }
+            } else if trainer.table.contains_key("novelty") {
+                return Err(trainer.error("novelty", "is only used by the novelty mode"));
+            }
+
+            if let Some(speciation) = trainer.section("speciation")? {
+                speciation.allow_keys(&["threshold", "stagnation_limit", "node_type", "connection", "constant"])?;
+                let mut speciation_settings = SpeciationSettings::default();
+                speciation_settings.threshold = speciation.ranged_number("threshold", speciation_settings.threshold, 0.0, f64::MAX)?;
+                speciation_settings.stagnation_limit = speciation.count("stagnation_limit", speciation_settings.stagnation_limit, 1)?;
+                let coefficients = &mut speciation_settings.coefficients;
+                coefficients.node_type = speciation.ranged_number("node_type", coefficients.node_type, 0.0, f64::MAX)?;
+                coefficients.connection = speciation.ranged_number("connection", coefficients.connection, 0.0, f64::MAX)?;
+                coefficients.constant = speciation.ranged_number("constant", coefficients.constant, 0.0, f64::MAX)?;
+                settings.speciation = Some(speciation_settings);
+            }
+
+            if let Some(novelty) = trainer.section("novelty")? {
+                novelty.allow_keys(&["nearest_neighbors", "archive_threshold", "max_archive_size", "novelty_weight"])?;
+                let defaults = NoveltySettings::default();
+                settings.novelty = NoveltySettings {
+                    nearest_neighbors: novelty.count("nearest_neighbors", defaults.nearest_neighbors, 1)?,
+                    archive_threshold: novelty.ranged_number("archive_threshold", defaults.archive_threshold, 0.0, f64::MAX)?,
+                    max_archive_size: novelty.count("max_archive_size", defaults.max_archive_size, 0)?,
+                    novelty_weight: novelty.ranged_number("novelty_weight", defaults.novelty_weight, 0.0, 1.0)?,
+                };
+            }
+
+            if let Some(refinement) = trainer.section("refinement")? {
+                refinement.allow_keys(&["method", "step_size", "evaluations", "network_count"])?;
+                let method = match refinement.text("method")?.unwrap_or("nelder_mead") {
+                    "hill_climbing" => Method::HillClimbing,
+                    "nelder_mead" => Method::NelderMead,
+                    "cma_es" => Method::CmaEs,
+                    _ => return Err(refinement.error("method", "must be hill_climbing, nelder_mead or cma_es")),
+                };
+                let defaults = ConstantOptimizer::new(method);
+                settings.refinement = Some(Refinement {
+                    optimizer: ConstantOptimizer {
+                        step_size: refinement.ranged_number("step_size", defaults.step_size, f64::MIN_POSITIVE, f64::MAX)?,
+                        evaluations: refinement.count("evaluations", defaults.evaluations, 1)?,
+                        ..defaults
+                    },
+                    network_count: refinement.count("network_count", 1, 1)?,
+                });
+            }
+        }
+
+        if let Some(nodes) = root.section("nodes")? {
+            nodes.allow_keys(&["profile", "families", "enable", "disable", "prevalence"])?;
+            let table = &mut config.node_selection_table;
+            let families = nodes.text_list("families")?;
+            let enabled_ids = nodes.id_list("enable")?;
+            if let Some(profile) = nodes.text("profile")? {
+                *table = match Profile::preset(profile) {
+                    Some(preset) => preset.node_selection_table,
+                    None => Profile::load(profile).map_err(|error| nodes.error("profile", &format!("not a preset, and could not be loaded as a profile file ({:?})", error)))?.node_selection_table,
+                };
+            } else if families.is_some() || !enabled_ids.is_empty() {
+                *table = NodeSelectionTable::new();
+            }
+            for (index, family) in families.unwrap_or_default().iter().enumerate() {
+                match *family {
+                    "all" => table.enable_all(),
+                    "logic" => table.enable_logic(),
+                    "simple_arithmetic" => table.enable_simple_arithmetic(),
+                    "advanced_arithmetic" => table.enable_advanced_arithmetic(),
+                    "string_modification" => table.enable_string_modification(),
+                    "special_functions" => table.enable_special_functions(),
+                    "statistics" => table.enable_statistics(),
+                    "activation" => table.enable_activation(),
+                    "memory" => table.enable_memory(),
+                    "integer" => table.enable_integer(),
+                    "conditional" => table.enable_conditional(),
+                    _ => return Err(nodes.error(&format!("families[{}]", index), "unknown node family")),
+                }
+            }
+            for id in enabled_ids {
+                table.enable_by_id(id);
+            }
+            for id in nodes.id_list("disable")? {
+                table.disable_by_id(id);
+            }
+            if let Some(prevalence) = nodes.section("prevalence")? {
+                for key in prevalence.table.keys() {
+                    let id: usize = key.parse().map_err(|_| prevalence.error(key, "must be a node type id"))?;
+                    if table.get_prevalence_by_id(id).is_err() {
+                        return Err(prevalence.error(key, "node type is not enabled"));
+                    }
+                    let value = prevalence.ranged_number(key, 0.0, 0.0, 1.0)?;
+                    table.set_prevalence_by_id(id, value as f32).map_err(|_| prevalence.error(key, "must be between 0 and 1"))?;
+                }
+            }
+            if !table.descriptors().iter().any(|descriptor| descriptor.prevalence() > 0.0) {
+                return Err(nodes.error("", "no node type is enabled with a prevalence above 0"));
+            }
+        }
+        Ok(config)
+    } //Reads a config, checking every key and value
+
+    pub fn load(path: &str) -> Result<TrainingConfig, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|_| ConfigError { key: String::new(), message: format!("could not read {}", path) })?;
+        TrainingConfig::from_toml_str(&text)
+    }
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_maps_onto_settings_and_table() {
+        let config = TrainingConfig::from_toml_str("
+            [training]
+            generations = 20
+            metric = \"huber\"
+            huber_delta = 0.5
+            objectives = [\"loss\", \"node_count\"]
+
+            [trainer]
+            population_size = 50
+            mutation_rate = 0.5
+            selection = \"penalized\"
+            penalty = { node = 0.01 }
+
+            [trainer.speciation]
+            threshold = 2
+
+            [nodes]
+            families = [\"simple_arithmetic\"]
+            enable = [113]
+            disable = [204]
+            prevalence = { 201 = 0.25 }
+        ").unwrap();
+        assert_eq!(config.generations, 20);
+        assert_eq!(config.metric, Metric::Huber(0.5));
+        assert_eq!(config.objectives, vec![Objective::Loss, Objective::NodeCount]);
+        assert_eq!(config.settings.population_size, 50);
+        assert_eq!(config.settings.mutation_rate, 0.5);
+        assert_eq!(config.settings.elitism, 1);
+        assert_eq!(config.settings.selection, Selection::Penalized(ComplexityWeights { node: 0.01, connection: 0.0, depth: 0.0, effective_node: 0.0 }));
+        assert_eq!(config.settings.speciation.unwrap().threshold, 2.0);
+        assert_eq!(config.node_selection_table.get_prevalence_by_id(201).unwrap(), 0.25);
+        assert_eq!(config.node_selection_table.get_prevalence_by_id(113).unwrap(), 1.0);
+        assert!(config.node_selection_table.get_prevalence_by_id(204).is_err());
+        assert!(config.node_selection_table.get_prevalence_by_id(101).is_err());
+        assert!(TrainingConfig::from_toml_str("").unwrap().node_selection_table.get_prevalence_by_id(401).is_ok());
+
+        let config = TrainingConfig::from_toml_str("[nodes]\nprofile = \"symbolic regression\"\nenable = [101]\nprevalence = { 222 = 0.5 }").unwrap();
+        assert_eq!(config.node_selection_table.get_prevalence_by_id(222).unwrap(), 0.5);
+        assert_eq!(config.node_selection_table.get_prevalence_by_id(223).unwrap(), 0.2);
+        assert!(config.node_selection_table.get_prevalence_by_id(101).is_ok());
+    }
+
+    #[test]
+    fn errors_name_the_offending_key() {
+        let error = |text: &str| TrainingConfig::from_toml_str(text).unwrap_err().to_string();
+        assert_eq!(error("[trainer]\nmutation_rat = 0.5"), "trainer.mutation_rat: unknown key, expected one of population_size, hidden_layer_count, max_nodes_per_layer, constant_range, mutation_rate, tournament_size, elitism, seed, selection, lexicographic_tolerance, penalty, speciation, novelty, refinement");
+        assert_eq!(error("[trainer]\nmutation_rate = 2"), "trainer.mutation_rate: must be between 0 and 0.99");
+        assert_eq!(error("[trainer]\npopulation_size = 0"), "trainer.population_size: must be a whole number of at least 1");
+        assert_eq!(error("[trainer]\npopulation_size = 5\nelitism = 6"), "trainer.elitism: can't be larger than population_size");
+        assert_eq!(error("[training]\nobjectives = [\"loss\", \"size\"]"), "training.objectives[1]: unknown objective");
+        assert_eq!(error("[nodes]\nfamilies = [\"logic\"]\nprevalence = { 201 = 0.5 }"), "nodes.prevalence.201: node type is not enabled");
+        assert_eq!(error("[nodes]\nprevalence = { 201 = 1.5 }"), "nodes.prevalence.201: must be between 0 and 1");
+        assert_eq!(error("[nodes]\nenable = [999]"), "nodes.enable[0]: must be a known node type id");
+        assert_eq!(error("[nodes]\nprofile = \"no such profile\""), "nodes.profile: not a preset, and could not be loaded as a profile file (FileRead)");
+        assert_eq!(error("[nodes]\nfamilies = []"), "nodes: no node type is enabled with a prevalence above 0");
+        assert!(error("[trainer\n").starts_with("TOML parse error at line 1"));
+        assert_eq!(error("[trainer]\npenalty = { node = 0.1 }"), "trainer.penalty: is only used by penalized selection");
+        assert_eq!(error("[trainer]\nselection = \"penalized\"\npenalty = {}\nlexicographic_tolerance = 0.1"), "trainer.lexicographic_tolerance: is only used by lexicographic selection");
+        assert_eq!(error("[trainer.novelty]\nnovelty_weight = 0.5"), "trainer.novelty: is only used by the novelty mode");
+        assert_eq!(error("[training]\nmode = \"novelty\"\n[trainer.refinement]"), "trainer.refinement: is not used by the novelty mode");
+        assert_eq!(error("[training]\nmode = \"novelty\"\nobjectives = [\"loss\", \"depth\"]"), "training.mode: novelty needs a single objective");
+        let config = TrainingConfig::from_toml_str("[training]\nmode = \"novelty\"\n[trainer.novelty]\nnovelty_weight = 0.25").unwrap();
+        assert_eq!((config.mode, config.settings.novelty.novelty_weight), (Mode::Novelty, 0.25));
+    }
+}
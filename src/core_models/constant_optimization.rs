@@ -0,0 +1,374 @@
+use crate::core_models::dannn::Network;
+use crate::core_models::random::Rng;
+
+/*
+Constants are the only real valued parameters of a network, so once a topology is fixed its constants can be tuned
+with ordinary numerical optimization. Every method here treats the constants of a network (in the order returned by
+Network::constants) as one vector and minimizes the fitness function (a loss) over it:
+ - Hill climbing: perturbs every constant with gaussian noise and keeps the change if it is no worse. The noise grows
+   after a success and shrinks after a failure.
+ - Nelder-Mead: moves a simplex of n + 1 points around, needs no randomness.
+ - CMA-ES: samples a population from a gaussian and adapts its mean, step size and covariance to the best samples,
+   the most robust of the three but needs more evaluations per step.
+The budget of every method is the amount of fitness evaluations and is never exceeded, a budget of zero evaluates
+nothing and reports an infinite loss. The network is always left with the best constants found, unless the fitness
+function changed its structure, in which case the network is left as it is and the best loss so far is returned.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    HillClimbing,
+    NelderMead,
+    CmaEs,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstantOptimizer {
+    pub method: Method,
+    pub step_size: f64, //Initial noise, simplex size, or sigma, relative to the scale of the constants
+    pub evaluations: usize, //Fitness evaluations the optimizer may use
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Refinement {
+    pub optimizer: ConstantOptimizer,
+    pub network_count: usize, //Amount of best networks refined every generation
+}
+
+impl ConstantOptimizer {
+    pub fn new(method: Method) -> ConstantOptimizer {
+        ConstantOptimizer {
+            method,
+            step_size: 1.0,
+            evaluations: 200,
+        }
+    }
+
+    pub fn optimize<F>(&self, network: &mut Network, rng: &mut Rng, mut fitness: F) -> f64 where F: FnMut(&mut Network, &mut Rng) -> f64 {
+        let start = network.constants();
+        let mut objective = |constants: &[f64], rng: &mut Rng| -> f64 {
+            if network.set_constants(constants).is_err() {
+                return f64::INFINITY; //The fitness function changed the structure of the network
+            }
+            let loss = fitness(network, rng);
+            if loss.is_nan() { f64::INFINITY } else { loss }
+        };
+
+        let (best, best_loss) = if self.evaluations == 0 {
+            (start, f64::INFINITY)
+        } else if start.is_empty() {
+            let loss = objective(&start, rng);
+            (start, loss)
+        } else {
+            match self.method {
+                Method::HillClimbing => hill_climbing(&start, self.step_size, self.evaluations, rng, &mut objective),
+                Method::NelderMead => nelder_mead(&start, self.step_size, self.evaluations, rng, &mut objective),
+                Method::CmaEs => cma_es(&start, self.step_size, self.evaluations, rng, &mut objective),
+            }
+        };
+        let _ = network.set_constants(&best);
+        best_loss
+    } //Tunes the constants of the network in place, returns the loss of the best constants
+}
+
+fn hill_climbing<F>(start: &[f64], step_size: f64, evaluations: usize, rng: &mut Rng, objective: &mut F) -> (Vec<f64>, f64) where F: FnMut(&[f64], &mut Rng) -> f64 {
+    let mut best = start.to_vec();
+    if evaluations == 0 {
+        return (best, f64::INFINITY);
+    }
+    let mut best_loss = objective(&best, rng);
+    let mut step_size = step_size;
+    for _ in 1..evaluations {
+        let candidate: Vec<f64> = best.iter().map(|value| value + step_size * rng.next_gaussian()).collect();
+        let loss = objective(&candidate, rng);
+        if loss <= best_loss {
+            best = candidate;
+            best_loss = loss;
+            step_size *= 1.5;
+        } else {
+            step_size *= 0.9; //Roughly the one fifth success rule
+        }
+    }
+    (best, best_loss)
+}
+
+fn nelder_mead<F>(start: &[f64], step_size: f64, evaluations: usize, rng: &mut Rng, objective: &mut F) -> (Vec<f64>, f64) where F: FnMut(&[f64], &mut Rng) -> f64 {
+    let dimensions = start.len();
+    let mut simplex = vec![start.to_vec()];
+    for dimension in 0..dimensions {
+        let mut point = start.to_vec();
+        point[dimension] += step_size;
+        simplex.push(point);
+    }
+    let mut losses: Vec<f64> = simplex.iter().take(evaluations).map(|point| objective(point, rng)).collect();
+    let mut used = losses.len();
+    if used < simplex.len() {
+        return match (0..losses.len()).min_by(|first, second| losses[*first].total_cmp(&losses[*second])) {
+            Some(best_index) => (simplex[best_index].clone(), losses[best_index]),
+            None => (start.to_vec(), f64::INFINITY),
+        };
+    } //The budget ran out before the simplex was complete
+
+    while used < evaluations {
+        let mut order: Vec<usize> = (0..simplex.len()).collect();
+        order.sort_by(|first, second| losses[*first].total_cmp(&losses[*second]));
+        simplex = order.iter().map(|index| simplex[*index].clone()).collect();
+        losses = order.iter().map(|index| losses[*index]).collect();
+        let worst = dimensions;
+
+        let centroid: Vec<f64> = (0..dimensions).map(|dimension| simplex[..worst].iter().map(|point| point[dimension]).sum::<f64>() / dimensions as f64).collect();
+        let along = |scale: f64| -> Vec<f64> {
+            centroid.iter().zip(&simplex[worst]).map(|(center, point)| center + scale * (point - center)).collect()
+        };
+
+        let reflected = along(-1.0);
+        let reflected_loss = objective(&reflected, rng);
+        used += 1;
+        if reflected_loss < losses[0] && used < evaluations {
+            let expanded = along(-2.0);
+            let expanded_loss = objective(&expanded, rng);
+            used += 1;
+            if expanded_loss < reflected_loss {
+                simplex[worst] = expanded;
+                losses[worst] = expanded_loss;
+            } else {
+                simplex[worst] = reflected;
+                losses[worst] = reflected_loss;
+            }
+        } else if reflected_loss < losses[worst - 1] || used == evaluations {
+            if reflected_loss < losses[worst] {
+                simplex[worst] = reflected;
+                losses[worst] = reflected_loss;
+            }
+        } else {
+            let contracted = along(0.5);
+            let contracted_loss = objective(&contracted, rng);
+            used += 1;
+            if contracted_loss < losses[worst] {
+                simplex[worst] = contracted;
+                losses[worst] = contracted_loss;
+            } else { //Shrink every point towards the best one
+                for index in 1..simplex.len() {
+                    if used == evaluations {
+                        break;
+                    }
+                    simplex[index] = simplex[0].iter().zip(&simplex[index]).map(|(best, point)| best + 0.5 * (point - best)).collect();
+                    losses[index] = objective(&simplex[index], rng);
+                    used += 1;
+                }
+            }
+        }
+    }
+
+    let best_index = (0..losses.len()).min_by(|first, second| losses[*first].total_cmp(&losses[*second])).unwrap();
+    (simplex[best_index].clone(), losses[best_index])
+}
+
+fn cma_es<F>(start: &[f64], sigma: f64, evaluations: usize, rng: &mut Rng, objective: &mut F) -> (Vec<f64>, f64) where F: FnMut(&[f64], &mut Rng) -> f64 {
+    //Follows "The CMA Evolution Strategy: A Tutorial" (Hansen), with the eigen decomposition redone every generation
+    let n = start.len();
+    let dimensions = n as f64;
+    let lambda = 4 + (3.0 * dimensions.ln()).floor() as usize;
+    let mu = lambda / 2;
+    let raw_weights: Vec<f64> = (0..mu).map(|index| (mu as f64 + 0.5).ln() - ((index + 1) as f64).ln()).collect();
+    let weight_sum: f64 = raw_weights.iter().sum();
+    let weights: Vec<f64> = raw_weights.iter().map(|weight| weight / weight_sum).collect();
+    let mu_effective = 1.0 / weights.iter().map(|weight| weight * weight).sum::<f64>();
+
+    let c_c = (4.0 + mu_effective / dimensions) / (dimensions + 4.0 + 2.0 * mu_effective / dimensions);
+    let c_sigma = (mu_effective + 2.0) / (dimensions + mu_effective + 5.0);
+    let c_1 = 2.0 / ((dimensions + 1.3).powi(2) + mu_effective);
+    let c_mu = (1.0 - c_1).min(2.0 * (mu_effective - 2.0 + 1.0 / mu_effective) / ((dimensions + 2.0).powi(2) + mu_effective));
+    let d_sigma = 1.0 + 2.0 * (((mu_effective - 1.0) / (dimensions + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+    let expected_norm = dimensions.sqrt() * (1.0 - 1.0 / (4.0 * dimensions) + 1.0 / (21.0 * dimensions * dimensions));
+
+    let mut mean = start.to_vec();
+    let mut sigma = sigma;
+    let mut covariance: Vec<Vec<f64>> = (0..n).map(|row| (0..n).map(|column| if row == column { 1.0 } else { 0.0 }).collect()).collect();
+    let mut path_c = vec![0.0; n];
+    let mut path_sigma = vec![0.0; n];
+
+    let mut best = start.to_vec();
+    let mut best_loss = objective(&best, rng);
+    let mut used = 1;
+    let mut generation = 0;
+    while used + lambda <= evaluations {
+        let (eigenvalues, eigenvectors) = symmetric_eigen(&covariance);
+        let scales: Vec<f64> = eigenvalues.iter().map(|value| value.max(1e-20).sqrt()).collect();
+
+        let mut samples = Vec::new();
+        for _ in 0..lambda {
+            let z: Vec<f64> = (0..n).map(|_| rng.next_gaussian()).collect();
+            let y: Vec<f64> = (0..n).map(|row| (0..n).map(|column| eigenvectors[row][column] * scales[column] * z[column]).sum()).collect();
+            let x: Vec<f64> = (0..n).map(|index| mean[index] + sigma * y[index]).collect();
+            let loss = objective(&x, rng);
+            if loss < best_loss {
+                best = x.clone();
+                best_loss = loss;
+            }
+            samples.push((loss, y));
+        }
+        used += lambda;
+        samples.sort_by(|first, second| first.0.total_cmp(&second.0));
+
+        let y_weighted: Vec<f64> = (0..n).map(|index| (0..mu).map(|rank| weights[rank] * samples[rank].1[index]).sum()).collect();
+        for index in 0..n {
+            mean[index] += sigma * y_weighted[index];
+        }
+
+        //C^(-1/2) * y_weighted, using C = B D^2 B^T
+        let rotated: Vec<f64> = (0..n).map(|column| (0..n).map(|row| eigenvectors[row][column] * y_weighted[row]).sum::<f64>() / scales[column]).collect();
+        let whitened: Vec<f64> = (0..n).map(|row| (0..n).map(|column| eigenvectors[row][column] * rotated[column]).sum()).collect();
+        for index in 0..n {
+            path_sigma[index] = (1.0 - c_sigma) * path_sigma[index] + (c_sigma * (2.0 - c_sigma) * mu_effective).sqrt() * whitened[index];
+        }
+        let path_sigma_norm = path_sigma.iter().map(|value| value * value).sum::<f64>().sqrt();
+        generation += 1;
+        let h_sigma = if path_sigma_norm / (1.0 - (1.0 - c_sigma).powi(2 * generation)).sqrt() < (1.4 + 2.0 / (dimensions + 1.0)) * expected_norm { 1.0 } else { 0.0 };
+        for index in 0..n {
+            path_c[index] = (1.0 - c_c) * path_c[index] + h_sigma * (c_c * (2.0 - c_c) * mu_effective).sqrt() * y_weighted[index];
+        }
+
+        for row in 0..n {
+            for column in 0..n {
+                let rank_one = path_c[row] * path_c[column] + (1.0 - h_sigma) * c_c * (2.0 - c_c) * covariance[row][column];
+                let rank_mu: f64 = (0..mu).map(|rank| weights[rank] * samples[rank].1[row] * samples[rank].1[column]).sum();
+                covariance[row][column] = (1.0 - c_1 - c_mu) * covariance[row][column] + c_1 * rank_one + c_mu * rank_mu;
+            }
+        }
+        sigma *= ((c_sigma / d_sigma) * (path_sigma_norm / expected_norm - 1.0)).exp();
+        if !sigma.is_finite() || sigma < 1e-300 {
+            break;
+        }
+    }
+    (best, best_loss)
+}
+
+fn symmetric_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    //Cyclic Jacobi rotations, returns the eigenvalues and a matrix with the matching eigenvectors as columns
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut vectors: Vec<Vec<f64>> = (0..n).map(|row| (0..n).map(|column| if row == column { 1.0 } else { 0.0 }).collect()).collect();
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n).flat_map(|row| (0..n).filter(move |column| *column != row).map(move |column| (row, column))).map(|(row, column)| a[row][column] * a[row][column]).sum();
+        if off_diagonal < 1e-22 {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = row_p.iter().zip(&row_q).map(|(apk, aqk)| c * apk - s * aqk).collect();
+                a[q] = row_p.iter().zip(&row_q).map(|(apk, aqk)| s * apk + c * aqk).collect();
+                for row in vectors.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|index| a[index][index]).collect(), vectors)
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant_network() -> Network {
+        //Outputs (input 0 - a) * b, where a and b are constants
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(202, 0);
+        let _ = network.generate_node_to_layer(203, 1);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_constant(0, 0, 0.0).unwrap();
+        network.connect_node(1, 0, 0, 0).unwrap();
+        network.connect_constant(1, 0, 1.0).unwrap();
+        network
+    }
+
+    fn target_loss(network: &mut Network, rng: &mut Rng) -> f64 {
+        //Target function is (x - 3) * 2
+        let mut total = 0.0;
+        for x in [-2.0, 0.0, 1.0, 4.0, 7.0] {
+            let output = network.evaluate(&[x], rng).unwrap()[0];
+            total += (output - (x - 3.0) * 2.0).powi(2);
+        }
+        total
+    }
+
+    #[test]
+    fn every_method_fits_constants() {
+        for method in [Method::HillClimbing, Method::NelderMead, Method::CmaEs] {
+            let mut network = constant_network();
+            let mut rng = Rng::new(11);
+            let optimizer = ConstantOptimizer { evaluations: 2000, ..ConstantOptimizer::new(method) };
+            let loss = optimizer.optimize(&mut network, &mut rng, target_loss);
+            assert!(loss < 1e-3, "{:?} reached a loss of {}", method, loss);
+            let constants = network.constants();
+            assert!((constants[0] - 3.0).abs() < 0.05 && (constants[1] - 2.0).abs() < 0.05, "{:?} found {:?}", method, constants);
+            assert_eq!(target_loss(&mut network, &mut rng), loss);
+        }
+    }
+
+    #[test]
+    fn budget_is_never_exceeded() {
+        for method in [Method::HillClimbing, Method::NelderMead, Method::CmaEs] {
+            for evaluations in [0, 1, 2, 3, 4, 7, 50] {
+                let mut network = constant_network();
+                let mut rng = Rng::new(5);
+                let mut calls = 0;
+                let optimizer = ConstantOptimizer { evaluations, ..ConstantOptimizer::new(method) };
+                let loss = optimizer.optimize(&mut network, &mut rng, |network, rng| {
+                    calls += 1;
+                    target_loss(network, rng)
+                });
+                assert!(calls <= evaluations, "{:?} used {} of {} evaluations", method, calls, evaluations);
+                assert_eq!(loss.is_infinite(), evaluations == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn fitness_changing_the_structure_does_not_panic() {
+        for method in [Method::HillClimbing, Method::NelderMead, Method::CmaEs] {
+            let mut network = constant_network();
+            let mut rng = Rng::new(5);
+            let optimizer = ConstantOptimizer { evaluations: 30, ..ConstantOptimizer::new(method) };
+            let loss = optimizer.optimize(&mut network, &mut rng, |network, rng| {
+                let loss = target_loss(network, rng);
+                network.connect_constant(1, 0, 1.0).unwrap();
+                loss
+            });
+            assert!(loss.is_finite());
+        }
+    }
+
+    #[test]
+    fn eigen_decomposition() {
+        let matrix = vec![vec![2.0, 1.0], vec![1.0, 2.0]];
+        let (mut values, vectors) = symmetric_eigen(&matrix);
+        for column in 0..2 {
+            let product: Vec<f64> = (0..2).map(|row| (0..2).map(|k| matrix[row][k] * vectors[k][column]).sum()).collect();
+            assert!((product[0] - values[column] * vectors[0][column]).abs() < 1e-9);
+            assert!((product[1] - values[column] * vectors[1][column]).abs() < 1e-9);
+        }
+        values.sort_by(f64::total_cmp);
+        assert!((values[0] - 1.0).abs() < 1e-9 && (values[1] - 3.0).abs() < 1e-9);
+    }
+}
@@ -1,4 +1,6 @@
 use std::collections::*;
+use regex::{Regex, RegexBuilder};
+use crate::core_models::random::Rng;
 /*
 TODO
  - Write node type descriptors for each node type
@@ -28,29 +30,808 @@ pub enum Error {
     InvalidNode,
     NodeAddition,
     LayerRetrieval,
-    NodeRetrieval
+    NodeRetrieval,
+    InvalidNodeType,
+    InvalidConnection,
+    InvalidInput,
+    NodeEvaluation,
+    DatasetRead,
+    DatasetParse,
+    InvalidColumn,
+    InvalidSplit,
+    FileRead,
+    FileWrite,
+    ModelParse,
+    ProfileParse,
+    ObjectiveCount
 }
 
-#[derive(Clone, Debug)]
-struct NodeSelectionDescriptor {
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NodeSelectionDescriptor {
     //Used for Storing information on a certain node type, used in the unsigned integer to Node Type Map.
-    minimum_connections: Option<usize>, //Minimum input connections that a node can have
-    maximum_connections: Option<usize>, //Maximum input connections that a node can have
-    description: String, //A String description of the node type
-    name: String, //the name of the node
-    prevalence: f32, //a float (from 0 to 1) that helps the trainer and the user define how much a certain node will be expressed
+    pub(crate) minimum_connections: Option<usize>, //Minimum input connections that a node can have
+    pub(crate) maximum_connections: Option<usize>, //Maximum input connections that a node can have
+    description: &'static str, //A String description of the node type
+    name: &'static str, //the name of the node
+    pub(crate) prevalence: f32, //a float (from 0 to 1) that helps the trainer and the user define how much a certain node will be expressed
 }
-#[derive(Debug)]
+pub(crate) static NODE_CATALOG: [(usize, NodeSelectionDescriptor); 106] = [
+    //Logic (1xx)
+    /*
+    Restricting logic states to zero and not zero leaves too many cases, and really ruins the point of the logic operations.
+    to fix this, a mean is generated between the largest and smallest number in the set, if the state of any given input is above
+    this mean, it is true, below, it is false. This allows for constants to set the parameters for state logic, while still giving
+    more capability for the logic system. This will be performed for every node type in this category. The functions will return the greater
+    value if true, and the smallest value if false. This is referred to as a "mapped truth value".
+     */
+    (101, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "performs an AND comparison of all inputs, omits least input during comparison",
+        name: "AND Gate",
+        prevalence: 1.0,
+    }),
+    (102, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "performs an OR comparison of all inputs, omits least and greatest input during comparison",
+        name: "OR Gate",
+        prevalence: 1.0,
+    }),
+    (103, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "performs an NOR comparison of all inputs, omits least and greatest input during comparison",
+        name: "NOR Gate",
+        prevalence: 1.0,
+    }),
+    (104, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "performs a NOT operation on one input, returns inverse of number to follow mapped truth value",
+        name: "NOT Gate",
+        prevalence: 1.0,
+    }),
+    (105, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Performs a NAND comparison of all inputs",
+        name: "NAND Gate",
+        prevalence: 1.0,
+    }),
+    (106, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Performs an XOR comparison of all inputs",
+        name: "XOR Gate",
+        prevalence: 1.0,
+    }),
+    (107, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "tests if all inputs are equal, returns the greatest input if true, and the least input if false",
+        name: "Equality Test",
+        prevalence: 1.0,
+    }),
+    (108, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "tests if first and second inputs are equal, returns the 3rd input if true, and 0 if false",
+        name: "Equality Test Gate",
+        prevalence: 1.0,
+    }),
+    (109, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "tests if first value is greater than second value, returns mapped truth value",
+        name: "Greater Than",
+        prevalence: 1.0,
+    }),
+    (110, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "If the first input is greater than the second input, the 3rd input is returned",
+        name: "Greater Than Gate",
+        prevalence: 1.0,
+    }),
+    (111, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "tests if first value is less than second value, returns mapped truth value",
+        name: "Less Than",
+        prevalence: 1.0,
+    }),
+    (112, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "tests if first value is less than second value, returns 3rd value if true, returns 0 if false",
+        name: "Less Than Gate",
+        prevalence: 1.0,
+    }),
+    (113, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Passes the input value to the output value",
+        name: "Passthrough",
+        prevalence: 1.0,
+    }),
+    (114, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "tests if first value is greater than or equal to second value, returns mapped truth value",
+        name: "Greater Than or Equal to",
+        prevalence: 1.0,
+    }),
+    (115, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "Tests if first value is greater than or equal to second value, returns 3rd value if true, returns 0 if False",
+        name: "Greater Than or Equal to Gate",
+        prevalence: 1.0,
+    }),
+    (116, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "tests if first value is less than or equal to second value, returns mapped truth value",
+        name: "Less Than Or Equal to",
+        prevalence: 1.0,
+    }),
+    (117, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "tests if first value is less than or equal to second value, returns 3rd value if true, returns 0 if false",
+        name: "Less Than or Equal to Gate",
+        prevalence: 1.0,
+    }),
+    //Arithmetic (2xx), 201 to 204 are simple arithmetic, the rest advanced
+    (201, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Adds all given numbers together",
+        name: "Add",
+        prevalence: 1.0,
+    }),
+    (202, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Subtracts all numbers from the first number",
+        name: "Subtract",
+        prevalence: 1.0,
+    }),
+    (203, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Multiplies all numbers",
+        name: "Multiply",
+        prevalence: 1.0,
+    }),
+    (204, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Divides all numbers from the first number",
+        name: "Divide",
+        prevalence: 1.0,
+    }),
+    (205, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Takes the n root of the first input, n being the second input",
+        name: "Root",
+        prevalence: 1.0,
+    }),
+    (206, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Calculates the first input to the second inputs power",
+        name: "Exponent",
+        prevalence: 1.0,
+    }),
+    (207, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the sine of the first input",
+        name: "Sin",
+        prevalence: 1.0,
+    }),
+    (208, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the cosine of the first input",
+        name: "Cos",
+        prevalence: 1.0,
+    }),
+    (209, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the tangent of the first input",
+        name: "Tan",
+        prevalence: 1.0,
+    }),
+    (210, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse sine of the first input",
+        name: "Arcsin",
+        prevalence: 1.0,
+    }),
+    (211, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse cosine of the first input",
+        name: "Arccos",
+        prevalence: 1.0,
+    }),
+    (212, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse tangent of the first input",
+        name: "Arctan",
+        prevalence: 1.0,
+    }),
+    (213, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the absolute value of the first input",
+        name: "Abs",
+        prevalence: 1.0,
+    }),
+    (214, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the rounded ceiling of the first input",
+        name: "Ceiling",
+        prevalence: 1.0,
+    }),
+    (215, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the rounded floor of the first input",
+        name: "Floor",
+        prevalence: 1.0,
+    }),
+    (216, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the rounded first input",
+        name: "Round",
+        prevalence: 1.0,
+    }),
+    (217, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the inverted first input",
+        name: "Invert sign",
+        prevalence: 1.0,
+    }),
+    (218, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Limits the first input to be no greater than the second input",
+        name: "Maximum Limiter",
+        prevalence: 1.0,
+    }),
+    (219, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Limits the first input to be no less than the second input",
+        name: "Minimum Limiter",
+        prevalence: 1.0,
+    }),
+    (220, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Returns the modulus of the first and second input",
+        name: "Modulus",
+        prevalence: 1.0,
+    }),
+    (221, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the inverse absolute value of the first input",
+        name: "Make Negative",
+        prevalence: 1.0,
+    }),
+    (222, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the cosecant of the first input",
+        name: "Cosecant",
+        prevalence: 1.0,
+    }),
+    (223, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the secant of the first input",
+        name: "Secant",
+        prevalence: 1.0,
+    }),
+    (224, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the cotangent of the first input",
+        name: "Cotangent",
+        prevalence: 1.0,
+    }),
+    (225, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the natural logarithm of the absolute value of the first input",
+        name: "Natural Log",
+        prevalence: 1.0,
+    }),
+    (226, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Returns the logarithm of the absolute value of the first input, in the base of the absolute value of the second input",
+        name: "Logarithm",
+        prevalence: 1.0,
+    }),
+    (227, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns e to the power of the first input",
+        name: "Exp",
+        prevalence: 1.0,
+    }),
+    (228, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the hyperbolic sine of the first input",
+        name: "Sinh",
+        prevalence: 1.0,
+    }),
+    (229, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the hyperbolic cosine of the first input",
+        name: "Cosh",
+        prevalence: 1.0,
+    }),
+    (230, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse hyperbolic sine of the first input",
+        name: "Arcsinh",
+        prevalence: 1.0,
+    }),
+    (231, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse hyperbolic cosine of the first input",
+        name: "Arccosh",
+        prevalence: 1.0,
+    }),
+    (232, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Calculates the inverse hyperbolic tangent of the first input",
+        name: "Arctanh",
+        prevalence: 1.0,
+    }),
+    //String modification (3xx)
+    (301, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Removes instances of second value from first value",
+        name: "String Remove",
+        prevalence: 1.0,
+    }),
+    (302, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "Replace instances of 2nd value from first value with 3rd value",
+        name: "String Replace",
+        prevalence: 1.0,
+    }),
+    (303, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Count amount of instances of 2nd Value inside 1st value",
+        name: "String Count",
+        prevalence: 1.0,
+    }),
+    (304, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Concatenate all inputs",
+        name: "Concatenate",
+        prevalence: 1.0,
+    }),
+    (305, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the amount of characters in the first input",
+        name: "String Length",
+        prevalence: 1.0,
+    }),
+    (306, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "Returns the characters of the first input starting at the second input, as many as the third input",
+        name: "Substring",
+        prevalence: 1.0,
+    }),
+    (307, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the first input in upper case",
+        name: "Uppercase",
+        prevalence: 1.0,
+    }),
+    (308, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the first input in lower case",
+        name: "Lowercase",
+        prevalence: 1.0,
+    }),
+    (309, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(4),
+        description: "Tests if the first input contains the second, returns a mapped truth value of the third and fourth inputs (1 and 0 if not connected)",
+        name: "String Contains",
+        prevalence: 1.0,
+    }),
+    (310, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Returns the character index of the first instance of the second input inside the first input, -1 if there is none",
+        name: "Index Of",
+        prevalence: 1.0,
+    }),
+    (311, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "Splits the first input at every instance of the second input and returns the part picked by the third input, empty if there is no such part",
+        name: "Split Nth",
+        prevalence: 1.0,
+    }),
+    (312, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Removes whitespace from both ends of the first input",
+        name: "Trim",
+        prevalence: 1.0,
+    }),
+    (313, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Reverses the characters of the first input",
+        name: "Reverse",
+        prevalence: 1.0,
+    }),
+    (314, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Reads the first input as a number, 0 if it is not one",
+        name: "Parse Number",
+        prevalence: 1.0,
+    }),
+    (315, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(2),
+        description: "Writes the first input as text, with as many decimals as the second input if connected",
+        name: "Number To String",
+        prevalence: 1.0,
+    }),
+    (316, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(4),
+        description: "Tests if the regular expression in the first input matches the second input, returns a mapped truth value of the third and fourth inputs (1 and 0 if not connected)",
+        name: "Regex Match",
+        prevalence: 1.0,
+    }),
+    (317, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Counts the matches of the regular expression in the first input inside the second input",
+        name: "Regex Count",
+        prevalence: 1.0,
+    }),
+    (318, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Returns the first capture group of the first match of the regular expression in the first input inside the second input, the whole match if the expression has no groups, empty text if the group took no part in the match",
+        name: "Regex Capture",
+        prevalence: 1.0,
+    }),
+    //Special functions (4xx)
+    (401, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Generate a random integer between two values",
+        name: "Random Number",
+        prevalence: 1.0,
+    }),
+    (402, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Draws a random number from a normal distribution, the first input is the mean and the second the standard deviation",
+        name: "Gaussian Random",
+        prevalence: 1.0,
+    }),
+    (403, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Draws a random number between two values, any fraction included",
+        name: "Uniform Random",
+        prevalence: 1.0,
+    }),
+    (404, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(3),
+        description: "Is true with the probability of the first input (limited to 0 to 1), returns a mapped truth value of the second and third inputs (1 and 0 if not connected)",
+        name: "Bernoulli",
+        prevalence: 1.0,
+    }),
+    (405, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns one of its inputs, picked at random",
+        name: "Random Choice",
+        prevalence: 1.0,
+    }),
+    //Statistics (5xx)
+    (501, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the mean of all inputs",
+        name: "Mean",
+        prevalence: 1.0,
+    }),
+    (502, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the median of all inputs, the mean of the middle two for an even amount",
+        name: "Median",
+        prevalence: 1.0,
+    }),
+    (503, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the least input",
+        name: "Minimum",
+        prevalence: 1.0,
+    }),
+    (504, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the greatest input",
+        name: "Maximum",
+        prevalence: 1.0,
+    }),
+    (505, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the population variance of all inputs",
+        name: "Variance",
+        prevalence: 1.0,
+    }),
+    (506, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the population standard deviation of all inputs",
+        name: "Standard Deviation",
+        prevalence: 1.0,
+    }),
+    (507, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the greatest input minus the least input",
+        name: "Range",
+        prevalence: 1.0,
+    }),
+    (508, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Counts the inputs that are greater than the mean of all inputs",
+        name: "Count Above Mean",
+        prevalence: 1.0,
+    }),
+    //Activation functions (6xx)
+    (601, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the logistic sigmoid of the first input, between 0 and 1",
+        name: "Sigmoid",
+        prevalence: 1.0,
+    }),
+    (602, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the hyperbolic tangent of the first input, between -1 and 1",
+        name: "Tanh",
+        prevalence: 1.0,
+    }),
+    (603, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the first input if it is positive, 0 otherwise",
+        name: "ReLU",
+        prevalence: 1.0,
+    }),
+    (604, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the first input if it is positive, the first input times 0.01 otherwise",
+        name: "Leaky ReLU",
+        prevalence: 1.0,
+    }),
+    (605, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns ln(1 + e^x) of the first input, a smooth ReLU",
+        name: "Softplus",
+        prevalence: 1.0,
+    }),
+    (606, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns e^(-x^2) of the first input, 1 at 0 and falling off to both sides",
+        name: "Gaussian",
+        prevalence: 1.0,
+    }),
+    (607, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns 1 if the first input is 0 or more, 0 otherwise",
+        name: "Step",
+        prevalence: 1.0,
+    }),
+    (608, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Sums all inputs, an input followed by a constant connection is multiplied by that constant first (a constant on its own is a bias)",
+        name: "Weighted Sum",
+        prevalence: 1.0,
+    }),
+    //Memory (7xx), these keep a value between evaluations, see run_memory_node
+    (701, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Returns the first input of the previous evaluation, 0 on the first evaluation",
+        name: "Delay",
+        prevalence: 1.0,
+    }),
+    (702, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(2),
+        description: "Adds the first input to a running sum and returns it, the sum is multiplied by the second input (a decay between 0 and 1, 1 if not connected) first",
+        name: "Accumulator",
+        prevalence: 1.0,
+    }),
+    (703, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Remembers 1 once the first input is greater than the second (set) and 0 once the second is greater than the first (reset), returns what it remembers",
+        name: "Latch",
+        prevalence: 1.0,
+    }),
+    (704, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(2),
+        description: "Counts the evaluations where the first input is above 0 and returns the count, the second input resets the count to 0 when above 0",
+        name: "Counter",
+        prevalence: 1.0,
+    }),
+    //Integer and bitwise (8xx), see to_integer
+    (801, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Performs a bitwise AND of all inputs as integers",
+        name: "Bitwise AND",
+        prevalence: 1.0,
+    }),
+    (802, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Performs a bitwise OR of all inputs as integers",
+        name: "Bitwise OR",
+        prevalence: 1.0,
+    }),
+    (803, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Performs a bitwise XOR of all inputs as integers",
+        name: "Bitwise XOR",
+        prevalence: 1.0,
+    }),
+    (804, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Flips every bit of the first input as an integer",
+        name: "Bitwise NOT",
+        prevalence: 1.0,
+    }),
+    (805, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Shifts the first input left by the second input bits, bits shifted past the top are lost",
+        name: "Shift Left",
+        prevalence: 1.0,
+    }),
+    (806, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Shifts the first input right by the second input bits, keeping its sign",
+        name: "Shift Right",
+        prevalence: 1.0,
+    }),
+    (807, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Counts the bits that are set in the first input as an integer",
+        name: "Popcount",
+        prevalence: 1.0,
+    }),
+    (808, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: Some(2),
+        description: "Divides the first input by the second as integers, rounding toward zero, dividing by zero returns 0",
+        name: "Integer Division",
+        prevalence: 1.0,
+    }),
+    (809, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: Some(1),
+        description: "Drops the fraction of the first input, limited to the integer range",
+        name: "Truncate",
+        prevalence: 1.0,
+    }),
+    //Conditional (9xx), If Then Else, Switch and If Greater are in run_value_node so they can pick text too
+    (901, NodeSelectionDescriptor {
+        minimum_connections: Some(3),
+        maximum_connections: Some(3),
+        description: "Returns the second input if the first input is above 0, the third input otherwise (a gate node that passes 1 makes a condition)",
+        name: "If Then Else",
+        prevalence: 1.0,
+    }),
+    (902, NodeSelectionDescriptor {
+        minimum_connections: Some(2),
+        maximum_connections: None,
+        description: "Returns the input picked by the first input, rounded and limited to the other inputs (0 picks the second input, 1 the third, ...)",
+        name: "Switch",
+        prevalence: 1.0,
+    }),
+    (903, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the index of the greatest input, the first one if several are equally great (Maximum returns the value)",
+        name: "Argmax",
+        prevalence: 1.0,
+    }),
+    (904, NodeSelectionDescriptor {
+        minimum_connections: Some(1),
+        maximum_connections: None,
+        description: "Returns the index of the least input, the first one if several are equally small (Minimum returns the value)",
+        name: "Argmin",
+        prevalence: 1.0,
+    }),
+    (905, NodeSelectionDescriptor {
+        minimum_connections: Some(4),
+        maximum_connections: Some(4),
+        description: "Returns the third input if the first input is greater than the second, the fourth input otherwise",
+        name: "If Greater",
+        prevalence: 1.0,
+    }),
+]; //Every node type with its default descriptor, in ascending id order. The tables, the families and the name lookups are all built from this
+
+#[derive(Clone, Debug)]
 pub struct NodeSelectionTable { //Table of unsigned integers to Node Descriptors object
-    node_type_mapping_descriptors: HashMap<usize, NodeSelectionDescriptor>,
+    pub(crate) node_type_mapping_descriptors: HashMap<usize, NodeSelectionDescriptor>,
+}
+
+impl Default for NodeSelectionTable {
+    fn default() -> NodeSelectionTable {
+        NodeSelectionTable::new()
+    }
 }
 
 impl NodeSelectionTable { //Associated functions with NodeSelectionTable - Modify which nodes are used
     pub fn new() -> NodeSelectionTable {
-        let mut table = NodeSelectionTable {
+        NodeSelectionTable {
             node_type_mapping_descriptors: HashMap::new(),
-        };
-        return table;
+        }
     }
     pub fn enable_all(&mut self){ //Runs all of the functions below that enable larger components of the table
         self.enable_logic();
@@ -58,7 +839,11 @@ impl NodeSelectionTable { //Associated functions with NodeSelectionTable - Modif
         self.enable_advanced_arithmetic();
         self.enable_string_modification();
         self.enable_special_functions();
-
+        self.enable_statistics();
+        self.enable_activation();
+        self.enable_memory();
+        self.enable_integer();
+        self.enable_conditional();
     } //Enables all node types
 
     pub fn disable_all(&mut self){ //Same as enable_all but for disabling
@@ -67,523 +852,283 @@ impl NodeSelectionTable { //Associated functions with NodeSelectionTable - Modif
         self.disable_advanced_arithmetic();
         self.disable_string_modification();
         self.disable_special_functions();
+        self.disable_statistics();
+        self.disable_activation();
+        self.disable_memory();
+        self.disable_integer();
+        self.disable_conditional();
     } //Disables all node types
 
     pub fn enable_logic(&mut self){
-        /*
-        Restricting logic states to zero and not zero leaves too many cases, and really ruins the point of the logic operations.
-        to fix this, a mean is generated between the largest and smallest number in the set, if the state of any given input is above
-        this mean, it is true, below, it is false. This allows for constants to set the parameters for state logic, while still giving
-        more capability for the logic system. This will be performed for every node type in this category. The functions will return the greater
-        value if true, and the smallest value if false. This is referred to as a "mapped truth value".
-         */
-        self.node_type_mapping_descriptors.insert(101, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("performs an AND comparison of all inputs, omits least input during comparison"),
-            name: String::from("AND Gate"),
-            prevalence: 1.0,
-        });
-        self.node_type_mapping_descriptors.insert(102, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("performs an OR comparison of all inputs, omits least and greatest input during comparison"),
-            name: String::from("OR Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(103, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("performs an NOR comparison of all inputs, omits least and greatest input during comparison"),
-            name: String::from("NOR Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(104, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("performs a NOT operation on one input, returns inverse of number to follow mapped truth value"),
-            name: String::from("NOT Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(105, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Performs a NAND comparison of all inputs"),
-            name: String::from("NAND Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(106, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Performs an XOR comparison of all inputs"),
-            name: String::from("XOR Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(107, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("tests if all inputs are equal, returns the greatest input if true, and the least input if false"),
-            name: String::from("Equality Test"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(108, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("tests if first and second inputs are equal, returns the 3rd input if true, and 0 if false"),
-            name: String::from("Equality Test"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(109, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("tests if first value is greater than second value, returns mapped truth value"),
-            name: String::from("Greater Than"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(110, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("If the first input is greater than the second input, the 3rd input is returned"),
-            name: String::from("Greater Than Gate"),
-            prevalence: 1.0,
-
-        });
-        self.node_type_mapping_descriptors.insert(111, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-             maximum_connections: Some(2),
-            description: String::from("tests if first value is less than second value, returns mapped truth value"),
-            name: String::from("Less Than"),
-            prevalence: 1.0,
+        self.enable_range(101..=117);
+    } //Enables all logic node types
 
-        });
-        self.node_type_mapping_descriptors.insert(112, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("tests if first value is less than second value, returns 3rd value if true, returns 0 if false"),
-            name: String::from("Less Than Gate"),
-            prevalence: 1.0,
+    pub fn disable_logic(&mut self){
+        self.disable_range(101..=117);
+    } //Disables all logic node types
 
-        });
-        self.node_type_mapping_descriptors.insert(113, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Passes the input value to the output value"),
-            name: String::from("Passthrough"),
-            prevalence: 1.0,
+    pub fn enable_simple_arithmetic(&mut self){
+        self.enable_range(201..=204);
+    } //Enables all basic arithmetic node types
 
-        });
-        self.node_type_mapping_descriptors.insert(114, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("tests if first value is greater than or equal to second value, returns mapped truth value"),
-            name: String::from("Greater Than or Equal to"),
-            prevalence: 1.0,
+    pub fn disable_simple_arithmetic(&mut self){
+        self.disable_range(201..=204);
+    } //Disables all basic simple arithmetic node types
 
-        });
-        self.node_type_mapping_descriptors.insert(115, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("Tests if first value is greater than or equal to second value, returns 3rd value if true, returns 0 if False"),
-            name: String::from("Greater Than or Equal to Gate"),
-            prevalence: 1.0,
+    pub fn enable_advanced_arithmetic(&mut self){
+        self.enable_range(205..=232);
+    } //Enables all advanced arithmetic (Trig, sqrt, exponent, logarithms, hyperbolic functions, Limiters, Inverters)
 
-        });
-        self.node_type_mapping_descriptors.insert(116, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("tests if first value is less than or equal to second value, returns mapped truth value"),
-            name: String::from("Less Than Or Equal to"),
-            prevalence: 1.0,
+    pub fn disable_advanced_arithmetic(&mut self){
+        self.disable_range(205..=232);
+    } //Disables all Advanced arithmetic
 
-        });
-        self.node_type_mapping_descriptors.insert(117, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("tests if first value is less than or equal to second value, returns 3rd value if true, returns 0 if false"),
-            name: String::from("Less Than or Equal to Gate"),
-            prevalence: 1.0,
+    pub fn enable_string_modification(&mut self){
+        self.enable_range(301..=318);
+    } //Enables all string modification node types
 
-        });
-    } //Enables all logic node types
+    pub fn disable_string_modification(&mut self){
+        self.disable_range(301..=318);
+    } //Disables all string modification node types
 
-    pub fn disable_logic(&mut self){
-        self.node_type_mapping_descriptors.remove(&101);
-        self.node_type_mapping_descriptors.remove(&102);
-        self.node_type_mapping_descriptors.remove(&103);
-        self.node_type_mapping_descriptors.remove(&104);
-        self.node_type_mapping_descriptors.remove(&105);
-        self.node_type_mapping_descriptors.remove(&106);
-        self.node_type_mapping_descriptors.remove(&107);
-        self.node_type_mapping_descriptors.remove(&108);
-        self.node_type_mapping_descriptors.remove(&109);
-        self.node_type_mapping_descriptors.remove(&110);
-        self.node_type_mapping_descriptors.remove(&111);
-        self.node_type_mapping_descriptors.remove(&112);
-        self.node_type_mapping_descriptors.remove(&113);
-        self.node_type_mapping_descriptors.remove(&114);
-        self.node_type_mapping_descriptors.remove(&115);
-        self.node_type_mapping_descriptors.remove(&116);
-        self.node_type_mapping_descriptors.remove(&117);
-    } //Disables all logic node types
+    pub fn enable_special_functions(&mut self){
+        self.enable_range(401..=405);
+    } //Enables all node types with "special" characteristics
 
-    pub fn enable_simple_arithmetic(&mut self){
-        self.node_type_mapping_descriptors.insert(201, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Adds all given numbers together"),
-            name: String::from("Add"),
-            prevalence: 1.0,
+    pub fn disable_special_functions(&mut self){
+        self.disable_range(401..=405);
+    } //Disables all node types with "special" characteristics
 
-        });
-        self.node_type_mapping_descriptors.insert(202, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Subtracts all numbers from the first number"),
-            name: String::from("Subtract"),
-            prevalence: 1.0,
+    pub fn enable_statistics(&mut self){
+        self.enable_range(501..=508);
+    } //Enables all statistical node types (Mean, Median, Variance, ...)
 
-        });
-        self.node_type_mapping_descriptors.insert(203, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Multiplies all numbers"),
-            name: String::from("Multiply"),
-            prevalence: 1.0,
+    pub fn disable_statistics(&mut self){
+        self.disable_range(501..=508);
+    } //Disables all statistical node types
 
-        });
-        self.node_type_mapping_descriptors.insert(204, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Divides all numbers from the first number"),
-            name: String::from("Divide"),
-            prevalence: 1.0,
+    pub fn enable_activation(&mut self){
+        self.enable_range(601..=608);
+    } //Enables the neural network activation functions and the weighted sum
 
-        });
-    } //Enables all basic arithmetic node types
+    pub fn disable_activation(&mut self){
+        self.disable_range(601..=608);
+    } //Disables the neural network activation functions and the weighted sum
 
-    pub fn disable_simple_arithmetic(&mut self){
-        self.node_type_mapping_descriptors.remove(&201);
-        self.node_type_mapping_descriptors.remove(&202);
-        self.node_type_mapping_descriptors.remove(&203);
-        self.node_type_mapping_descriptors.remove(&204);
-    } //Disables all basic simple arithmetic node types
+    pub fn enable_memory(&mut self){
+        self.enable_range(701..=704);
+    } //Enables the node types that remember values between evaluations (Delay, Accumulator, Latch, Counter)
 
-    pub fn enable_advanced_arithmetic(&mut self){
-        self.node_type_mapping_descriptors.insert(205, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Takes the n root of the first input, n being the second input"),
-            name: String::from("Root"),
-            prevalence: 1.0,
+    pub fn disable_memory(&mut self){
+        self.disable_range(701..=704);
+    } //Disables the memory node types
 
-        });
-        self.node_type_mapping_descriptors.insert(206, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Calculates the first input to the second inputs power"),
-            name: String::from("Exponent"),
-            prevalence: 1.0,
+    pub fn enable_integer(&mut self){
+        self.enable_range(801..=809);
+    } //Enables the integer and bitwise node types (AND, OR, XOR, NOT, shifts, popcount, integer division, truncation)
 
-        });
-        self.node_type_mapping_descriptors.insert(207, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the sine of the first input"),
-            name: String::from("Sin"),
-            prevalence: 1.0,
+    pub fn disable_integer(&mut self){
+        self.disable_range(801..=809);
+    } //Disables the integer and bitwise node types
 
-        });
-        self.node_type_mapping_descriptors.insert(208, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the cosine of the first input"),
-            name: String::from("Cos"),
-            prevalence: 1.0,
+    pub fn enable_conditional(&mut self){
+        self.enable_range(901..=905);
+    } //Enables the node types that choose between their inputs (If Then Else, Switch, Argmax, Argmin)
 
-        });
-        self.node_type_mapping_descriptors.insert(209, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the tangent of the first input"),
-            name: String::from("Tan"),
-            prevalence: 1.0,
+    pub fn disable_conditional(&mut self){
+        self.disable_range(901..=905);
+    } //Disables the conditional node types
 
-        });
-        self.node_type_mapping_descriptors.insert(210, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the inverse sine of the first input"),
-            name: String::from("Arcsin"),
-            prevalence: 1.0,
+    fn enable_range(&mut self, ids: std::ops::RangeInclusive<usize>) {
+        for (id, descriptor) in NODE_CATALOG.iter().filter(|(id, _)| ids.contains(id)) {
+            self.node_type_mapping_descriptors.insert(*id, *descriptor);
+        }
+    } //Enables every catalog node type in the range, with its default prevalence
 
-        });
-        self.node_type_mapping_descriptors.insert(211, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the inverse cosine of the first input"),
-            name: String::from("Arccos"),
-            prevalence: 1.0,
+    fn disable_range(&mut self, ids: std::ops::RangeInclusive<usize>) {
+        self.node_type_mapping_descriptors.retain(|id, _| !ids.contains(id));
+    }
 
-        });
-        self.node_type_mapping_descriptors.insert(212, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Calculates the inverse tangent of the first input"),
-            name: String::from("Arctan"),
-            prevalence: 1.0,
+    pub(crate) fn template_descriptor(id: usize) -> Option<NodeSelectionDescriptor> {
+        NODE_CATALOG.iter().find(|(catalog_id, _)| *catalog_id == id).map(|(_, descriptor)| *descriptor)
+    } //Looks up the default descriptor of a node type, whether or not it is enabled in this table
 
-        });
-        self.node_type_mapping_descriptors.insert(213, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the absolute value of the first input"),
-            name: String::from("Abs"),
-            prevalence: 1.0,
+    pub fn node_type_name(id: usize) -> Option<String> {
+        NodeSelectionTable::template_descriptor(id).map(|descriptor| descriptor.name.to_string())
+    } //Name of any known node type, whether or not it is enabled in a table
 
-        });
-        self.node_type_mapping_descriptors.insert(214, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the rounded ceiling of the first input"),
-            name: String::from("Ceiling"),
-            prevalence: 1.0,
+    pub fn enable_by_id(&mut self, id: usize){
+        match NodeSelectionTable::template_descriptor(id) {
+            Some(descriptor) => self.node_type_mapping_descriptors.insert(id, descriptor),
+            None => panic!("Error while retrieving node from template map."),
+        };
+    } //Enables a certain node by ID
 
-        });
-        self.node_type_mapping_descriptors.insert(215, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the rounded floor of the first input"),
-            name: String::from("Floor"),
-            prevalence: 1.0,
+    pub fn disable_by_id(&mut self, id: usize){
+        self.node_type_mapping_descriptors.remove(&id);
+    } //Disables a certain node by ID
 
-        });
-        self.node_type_mapping_descriptors.insert(216, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the rounded first input"),
-            name: String::from("Round"),
-            prevalence: 1.0,
+    pub fn set_prevalence_by_id(&mut self, id: usize, prevalence: f32) -> Result<(), Error>{
+        if !(0.0..=1.0).contains(&prevalence) {
+            return Err(Error::PrevalenceConfigure);
+        }
+        match self.node_type_mapping_descriptors.get_mut(&id) {
+            Some(descriptor) => descriptor.prevalence = prevalence,
+            None => return Err(Error::InvalidNodeType), //Only enabled node types have a prevalence
+        }
+        Ok(())
+    } //Sets the prevalence of a certain node type
 
-        });
-        self.node_type_mapping_descriptors.insert(217, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the inverted first input"),
-            name: String::from("Invert sign"),
-            prevalence: 1.0,
+    pub fn get_prevalence_by_id(&self, id: usize) -> Result<f32, Error>{
+        match self.node_type_mapping_descriptors.get(&id) {
+            Some(descriptor) => Ok(descriptor.prevalence),
+            None => Err(Error::InvalidNodeType),
+        }
+    } //Gets the prevalence of a certain node type
 
-        });
-        self.node_type_mapping_descriptors.insert(218, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Limits the first input to be no greater than the second input"),
-            name: String::from("Maximum Limiter"),
-            prevalence: 1.0,
+    pub fn len(&self) -> usize {
+        self.node_type_mapping_descriptors.len()
+    } //Amount of enabled node types
 
-        });
-        self.node_type_mapping_descriptors.insert(219, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Limits the first input to be no less than the second input"),
-            name: String::from("Minimum Limiter"),
-            prevalence: 1.0,
+    pub fn is_empty(&self) -> bool {
+        self.node_type_mapping_descriptors.is_empty()
+    }
 
-        });
-        self.node_type_mapping_descriptors.insert(220, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Returns the modulus of the first and second input"),
-            name: String::from("Modulus"),
-            prevalence: 1.0,
+    pub fn is_enabled(&self, id: usize) -> bool {
+        self.node_type_mapping_descriptors.contains_key(&id)
+    }
 
-        });
-        self.node_type_mapping_descriptors.insert(221, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the inverse absolute value of the first input"),
-            name: String::from("Make Negative"),
-            prevalence: 1.0,
+    pub fn enabled_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.node_type_mapping_descriptors.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    } //Every enabled node type id, in ascending order
 
-        });
-        self.node_type_mapping_descriptors.insert(222, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the cosecant of the first input"),
-            name: String::from("Cosecant"),
-            prevalence: 1.0,
+    pub fn descriptor(&self, id: usize) -> Option<NodeDescriptor<'_>> {
+        self.node_type_mapping_descriptors.get(&id).map(|descriptor| NodeDescriptor { id, descriptor })
+    } //Read only view of an enabled node type
 
-        });
-        self.node_type_mapping_descriptors.insert(223, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the secant of the first input"),
-            name: String::from("Secant"),
-            prevalence: 1.0,
+    pub fn descriptors(&self) -> Vec<NodeDescriptor<'_>> {
+        self.enabled_ids().into_iter().filter_map(|id| self.descriptor(id)).collect()
+    } //Every enabled node type, in ascending id order
 
-        });
-        self.node_type_mapping_descriptors.insert(224, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: Some(1),
-            description: String::from("Returns the cotangent of the first input"),
-            name: String::from("Cotangent"),
-            prevalence: 1.0,
+    pub fn find_by_name(&self, name: &str) -> Vec<NodeDescriptor<'_>> {
+        self.descriptors().into_iter().filter(|descriptor| descriptor.name().eq_ignore_ascii_case(name)).collect()
+    } //Enabled node types with a name, ignoring case. Names are not unique, so this can return more than one
 
-        });
-    } //Enables all advanced arithmetic (Trig, sqrt, exponent, Limiters, Inverters)
+    pub fn filter_by_family(&self, family: usize) -> Vec<NodeDescriptor<'_>> {
+        self.descriptors().into_iter().filter(|descriptor| descriptor.family() == family).collect()
+    } //Enabled node types of a family (100 for logic, 200 for arithmetic, ...)
 
-    pub fn disable_advanced_arithmetic(&mut self){
-        self.node_type_mapping_descriptors.remove(&205);
-        self.node_type_mapping_descriptors.remove(&206);
-        self.node_type_mapping_descriptors.remove(&207);
-        self.node_type_mapping_descriptors.remove(&208);
-        self.node_type_mapping_descriptors.remove(&209);
-        self.node_type_mapping_descriptors.remove(&210);
-        self.node_type_mapping_descriptors.remove(&211);
-        self.node_type_mapping_descriptors.remove(&212);
-        self.node_type_mapping_descriptors.remove(&213);
-        self.node_type_mapping_descriptors.remove(&214);
-        self.node_type_mapping_descriptors.remove(&215);
-        self.node_type_mapping_descriptors.remove(&216);
-        self.node_type_mapping_descriptors.remove(&217);
-        self.node_type_mapping_descriptors.remove(&218);
-        self.node_type_mapping_descriptors.remove(&219);
-        self.node_type_mapping_descriptors.remove(&220);
-        self.node_type_mapping_descriptors.remove(&221);
-        self.node_type_mapping_descriptors.remove(&222);
-        self.node_type_mapping_descriptors.remove(&223);
-        self.node_type_mapping_descriptors.remove(&224);
-    } //Disables all Advanced arithmetic
+    pub fn filter_by_arity(&self, connection_count: usize) -> Vec<NodeDescriptor<'_>> {
+        self.descriptors().into_iter().filter(|descriptor| descriptor.accepts(connection_count)).collect()
+    } //Enabled node types that can take this many connections
 
-    pub fn enable_string_modification(&mut self){
-        self.node_type_mapping_descriptors.insert(301, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Removes instances of second value from first value"),
-            name: String::from("String Remove"),
-            prevalence: 1.0,
-        });
-        self.node_type_mapping_descriptors.insert(302, NodeSelectionDescriptor {
-            minimum_connections: Some(3),
-            maximum_connections: Some(3),
-            description: String::from("Replace instances of 2nd value from first value with 3rd value"),
-            name: String::from("String Replace"),
-            prevalence: 1.0,
+}
 
-        });
-        self.node_type_mapping_descriptors.insert(303, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Count amount of instances of 2nd Value inside 1st value"),
-            name: String::from("String Count"),
-            prevalence: 1.0,
+#[derive(Clone, Copy, Debug)]
+pub struct NodeDescriptor<'a> {
+    id: usize,
+    descriptor: &'a NodeSelectionDescriptor,
+}
 
-        });
-        self.node_type_mapping_descriptors.insert(304, NodeSelectionDescriptor {
-            minimum_connections: Some(1),
-            maximum_connections: None,
-            description: String::from("Concatenate all inputs"),
-            name: String::from("Concatenate"),
-            prevalence: 1.0,
+impl NodeDescriptor<'_> {
+    pub fn id(&self) -> usize {
+        self.id
+    }
 
-        });
-    } //Enables all string modification node types
+    pub fn family(&self) -> usize {
+        self.id / 100 * 100
+    } //The hundreds of the id, 100 for logic, 200 for arithmetic, ...
 
-    pub fn disable_string_modification(&mut self){
-        self.node_type_mapping_descriptors.remove(&301);
-        self.node_type_mapping_descriptors.remove(&302);
-        self.node_type_mapping_descriptors.remove(&303);
-        self.node_type_mapping_descriptors.remove(&304);
-    } //Disables all string modification node types
+    pub fn name(&self) -> &str {
+        self.descriptor.name
+    }
 
-    pub fn enable_special_functions(&mut self){
-        self.node_type_mapping_descriptors.insert(401, NodeSelectionDescriptor {
-            minimum_connections: Some(2),
-            maximum_connections: Some(2),
-            description: String::from("Generate a random integer between two values"),
-            name: String::from("Random Number"),
-            prevalence: 1.0,
+    pub fn description(&self) -> &str {
+        self.descriptor.description
+    }
 
-        });
-    } //Enables all node types with "special" characteristics
+    pub fn minimum_connections(&self) -> Option<usize> {
+        self.descriptor.minimum_connections
+    }
 
-    pub fn disable_special_functions(&mut self){
-        self.node_type_mapping_descriptors.remove(&401);
-    } //Disables all node types with "special" characteristics
+    pub fn maximum_connections(&self) -> Option<usize> {
+        self.descriptor.maximum_connections
+    }
 
-    pub fn enable_by_id(&mut self, id: usize){
-        let mut temporary_template_table = NodeSelectionTable::new();
-        temporary_template_table.enable_all();
-        let mut descriptor = match temporary_template_table.node_type_mapping_descriptors.get(&id) {
-            Some(descriptor) => descriptor,
-            None => panic!("Error while retrieving node from template map."),
-        };
-        self.node_type_mapping_descriptors.insert(id, NodeSelectionDescriptor {
-            minimum_connections: descriptor.minimum_connections,
-            maximum_connections: descriptor.maximum_connections,
-            description: descriptor.description.clone(),
-            name: descriptor.name.clone(),
-            prevalence: descriptor.prevalence,
+    pub fn prevalence(&self) -> f32 {
+        self.descriptor.prevalence
+    }
 
-        });
+    pub fn accepts(&self, connection_count: usize) -> bool {
+        connection_count >= self.minimum_connections().unwrap_or(0) && connection_count <= self.maximum_connections().unwrap_or(usize::MAX)
+    } //Whether a node of this type can have this many connections
+} //Read only view of a descriptor in a NodeSelectionTable
 
-    } //Enables a certain node by ID
 
-    pub fn disable_by_id(&mut self, id: usize){
-        self.node_type_mapping_descriptors.remove(&id);
-    } //Disables a certain node by ID
 
-    pub fn set_prevalence_by_id(&mut self, id: usize, prevalence: f32) -> Result<(), Error>{
-        todo!();
-        Ok(()) //temp to make my IDE stop whining
-    } //Sets the prevalence of a certain node type
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value { //A value flowing through the network, most nodes work on numbers but the string nodes work on text
+    Number(f64),
+    Text(String),
+}
 
-    pub fn get_prevalence_by_id(id: usize) -> f32{
-        todo!();
-        1.0 //again, a temp to make my IDE stop whining
+impl Value {
+    pub fn as_number(&self) -> f64 {
+        match self {
+            Value::Number(number) => *number,
+            Value::Text(text) => match text.trim().parse::<f64>() {
+                Ok(number) => number,
+                Err(_) => text.chars().count() as f64, //Text that is not a number counts as its length
+            },
+        }
     }
 
+    pub fn as_text(&self) -> String {
+        match self {
+            Value::Number(number) => number.to_string(),
+            Value::Text(text) => text.clone(),
+        }
+    }
 }
 
-
-
-#[derive(Debug)]
-struct Node<'a> { //Container for node type, data, state, so on
-    node_type_id: usize,
-    connections: Vec<ConnectionType<'a>>,
-    state: f64,
+#[derive(Clone, Debug)]
+pub struct Node { //Container for node type, data, state, so on
+    pub(crate) node_type_id: usize,
+    pub(crate) connections: Vec<ConnectionType>,
+    pub(crate) state: f64,
+    pub(crate) memory: f64, //Value kept between evaluations by memory nodes, 0 after a reset
+    pub(crate) pattern_cache: PatternCache, //Last pattern compiled by a regex node, see run_regex_node
 }
 
-impl<'a> Node<'a> {
-    fn new(node_type_id: usize) -> Node<'a> {
+pub(crate) type PatternCache = Option<(String, Option<Regex>)>; //A pattern and what it compiled to, None if it is not a valid regular expression
+
+impl Node {
+    fn new(node_type_id: usize) -> Node {
         Node {
             node_type_id,
             connections: Vec::new(),
-            state: 0.0
+            state: 0.0,
+            memory: 0.0,
+            pattern_cache: None,
         }
     }
 }
 
 
-#[derive(Debug)]
-enum ConnectionType<'a> {
-    Connection(&'a NodeConnection<'a>),
-    Constant(&'a Constant),
-    Input(&'a Input),
+#[derive(Clone, Debug)]
+pub(crate) enum ConnectionType {
+    Connection(NodeConnection),
+    Constant(Constant),
+    Input(Input),
 }
 
 pub trait Connection {
     fn get_value(&self) -> f64;
 }
 
-impl Connection for NodeConnection<'_> {
+impl Connection for NodeConnection {
     fn get_value(&self) -> f64 {
         self.value
     }
@@ -601,189 +1146,508 @@ impl Connection for Input {
     }
 }
 
-#[derive(Debug)]
-struct NodeConnection<'a> { //Container for Connection state, connecting Node, and so on
-    value: f64,
-    source_node: &'a Node<'a>,
+#[derive(Clone, Debug)]
+pub(crate) struct NodeConnection { //Container for Connection state, connecting Node, and so on
+    pub(crate) value: f64,
+    pub(crate) source_layer: usize, //Index of the layer holding the source node, always lower than the layer of the receiving node
+    pub(crate) source_node: usize, //Index of the source node inside of its layer
 }
 
-#[derive(Debug)]
-struct Constant { //A similar type (will be united under one trait) to Connection, but represents a constant
-    value: f64,
+#[derive(Clone, Debug)]
+pub(crate) struct Constant { //A similar type (will be united under one trait) to Connection, but represents a constant
+    pub(crate) value: f64,
 }
 
-#[derive(Debug)]
-struct Input { //A similar type (will be united under one trait) to Connection, but represents a connection to one of the networks inputs
-    value: f64,
-    input_id: usize,
+#[derive(Clone, Debug)]
+pub(crate) struct Input { //A similar type (will be united under one trait) to Connection, but represents a connection to one of the networks inputs
+    pub(crate) value: f64,
+    pub(crate) input_id: usize,
 }
 
-#[derive(Debug)]
-struct Layer<'a> { //Holds a certain amount of nodes.
-    nodes: Vec<Node<'a>>
+#[derive(Clone, Debug)]
+pub struct Layer { //Holds a certain amount of nodes.
+    pub(crate) nodes: Vec<Node>
 }
 
-impl<'a> Layer<'a> {
-    fn new() -> Layer<'a> {
+impl Layer {
+    fn new() -> Layer {
         Layer {
             nodes: Vec::new()
         }
     }
 }
 
-#[derive(Debug)]
-struct Network<'a> { //The overall container for the networks, its layers, connections, and nodes
-    layers: Vec<Layer<'a>>,
-    inputs: Vec<f64>,
-    node_selection_table: NodeSelectionTable,
+#[derive(Clone, Debug)]
+pub struct Network { //The overall container for the networks, its layers, connections, and nodes
+    pub(crate) layers: Vec<Layer>,
+    pub(crate) inputs: Vec<f64>,
+    pub(crate) node_selection_table: NodeSelectionTable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Complexity { //Size metrics of a network, used to keep evolved networks from bloating
+    pub node_count: usize,
+    pub connection_count: usize, //Every connection, including constants and inputs
+    pub depth: usize, //Longest chain of connected nodes ending at an output node
+    pub effective_node_count: usize, //Nodes that the output nodes actually depend on
+    pub family_histogram: BTreeMap<usize, usize>, //Amount of nodes per node family, keyed by the start of the id range (100, 200, ...)
 }
 
 
 
 
-impl<'a> Network<'a> {
-    fn new() -> Network<'a> {
-        let mut network = Network {
+impl Default for Network {
+    fn default() -> Network {
+        Network::new()
+    }
+}
+
+impl Network {
+    pub fn new() -> Network {
+        Network {
             layers: Vec::new(),
             inputs: Vec::new(),
             node_selection_table: NodeSelectionTable::new()
-        };
-        network
+        }
     }
-    fn generate_node_to_layer(&mut self, node_type: usize, layer_index: usize) -> Result<&mut Node<'a>, Error> {
-        if !(layer_index < self.layers.len()) { //Check to make sure layer index is valid
+    pub fn generate_node_to_layer(&mut self, node_type: usize, layer_index: usize) -> Result<&mut Node, Error> {
+        if layer_index >= self.layers.len() { //Check to make sure layer index is valid
             return Err(Error::InvalidLayer)
         }
-        let mut layer;
-        match self.layers.get_mut(layer_index) {
-            Some(retrieved_layer) => {layer = retrieved_layer},
-            None => {return Err(Error::NodeAddition)}
-        }
+        let layer = match self.layers.get_mut(layer_index) {
+            Some(retrieved_layer) => retrieved_layer,
+            None => return Err(Error::NodeAddition),
+        };
 
-        let mut new_node: Node = Node::new(node_type);
+        let new_node: Node = Node::new(node_type);
 
         layer.nodes.push(new_node);
 
-        return Ok(layer.nodes.last_mut().unwrap());
+        Ok(layer.nodes.last_mut().unwrap())
 
     }
 
-    fn generate_layer_to_network(&mut self) -> Result<&mut Layer<'a>, Error> {
-        let mut new_layer: Layer = Layer::new();
+    pub fn generate_layer_to_network(&mut self) -> Result<&mut Layer, Error> {
+        let new_layer: Layer = Layer::new();
 
         self.layers.push(new_layer);
 
         Ok(self.layers.last_mut().unwrap())
     }
 
-    fn get_layer(&mut self, layer_index: usize) -> Result<&mut Layer<'a>, Error> {
-        if !(layer_index < self.layers.len()) { //Check to make sure layer index is valid
+    pub fn get_layer(&mut self, layer_index: usize) -> Result<&mut Layer, Error> {
+        if layer_index >= self.layers.len() { //Check to make sure layer index is valid
             return Err(Error::InvalidLayer);
         }
         Ok(self.layers.get_mut(layer_index).unwrap())
     }
 
-    fn get_node(&mut self, layer_index: usize, node_index: usize) -> Result<&mut Node<'a>, Error> {
-        if !(layer_index < self.layers.len()) { //Check to make sure layer index is valid
+    pub fn get_node(&mut self, layer_index: usize, node_index: usize) -> Result<&mut Node, Error> {
+        if layer_index >= self.layers.len() { //Check to make sure layer index is valid
             return Err(Error::InvalidLayer);
         }
-        if !(node_index < self.layers.len()) { //Check to make sure node index is valid
+        if node_index >= self.layers[layer_index].nodes.len() { //Check to make sure node index is valid
             return Err(Error::InvalidNode);
         }
-        let mut layer = self.get_layer(layer_index).unwrap();
-        return Ok(layer.nodes.get_mut(node_index).unwrap());
+        let layer = self.get_layer(layer_index).unwrap();
+        Ok(layer.nodes.get_mut(node_index).unwrap())
 
     }
 
+    pub fn node_count(&self) -> usize {
+        self.layers.iter().map(|layer| layer.nodes.len()).sum()
+    } //Amount of nodes in every layer of the network
 
-}
-
-
-//Tests
-#[cfg(test)]
-mod tests {
-    use crate::core_models::dannn::run_node;
-
-    //DANNN Core Model Tests
-    #[test]
-    fn initialize() { //init test
-        let node_selection_table = super::NodeSelectionTable::new();
+    pub fn layer_sizes(&self) -> Vec<usize> {
+        self.layers.iter().map(|layer| layer.nodes.len()).collect()
     }
 
-    #[test]
-    fn node_selection_table_test() {
-        let mut table = super::NodeSelectionTable::new();
-        table.enable_all();
-        for (key, value) in &table.node_type_mapping_descriptors {
-            println!("{} - {}, {}", key, value.name, value.description);
+    pub fn output_count(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.nodes.len())
+    } //The nodes of the last layer are the outputs
+
+    pub fn used_inputs(&self) -> BTreeSet<usize> {
+        let mut inputs = BTreeSet::new();
+        for layer in &self.layers {
+            for node in &layer.nodes {
+                for connection in &node.connections {
+                    if let ConnectionType::Input(input) = connection {
+                        inputs.insert(input.input_id);
+                    }
+                }
+            }
         }
-    }
+        inputs
+    } //Every input id read by at least one node
+
+    pub fn node_type_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for layer in &self.layers {
+            for node in &layer.nodes {
+                *counts.entry(node.node_type_id).or_insert(0) += 1;
+            }
+        }
+        counts
+    } //Amount of nodes of each node type
 
-    #[test]
-    fn descriptor_enabling_by_id_test() {
-        let mut table = super::NodeSelectionTable::new();
-        table.enable_by_id(201);
-        for (key, value) in &table.node_type_mapping_descriptors {
-            let mut min: usize = 0;
-            let mut max: usize = 0;
-            if let Some(minimum_connections) = value.minimum_connections {
-                min = minimum_connections;
-            } else {
-                min = 0;
+    pub fn validate(&self, input_count: Option<usize>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.layers.is_empty() {
+            problems.push(String::from("the network has no layers"));
+        }
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            for (node_index, node) in layer.nodes.iter().enumerate() {
+                let descriptor = match NodeSelectionTable::template_descriptor(node.node_type_id) {
+                    Some(descriptor) => descriptor,
+                    None => {
+                        problems.push(format!("layer {} node {}: unknown node type {}", layer_index, node_index, node.node_type_id));
+                        continue;
+                    }
+                };
+                let connection_count = node.connections.len();
+                if connection_count < descriptor.minimum_connections.unwrap_or(0) || connection_count > descriptor.maximum_connections.unwrap_or(usize::MAX) {
+                    problems.push(format!("layer {} node {}: {} has {} connections", layer_index, node_index, descriptor.name, connection_count));
+                }
+                for connection in &node.connections {
+                    match connection {
+                        ConnectionType::Input(input) if input_count.is_some_and(|input_count| input.input_id >= input_count) => {
+                            problems.push(format!("layer {} node {}: reads input {} of {}", layer_index, node_index, input.input_id, input_count.unwrap_or(0)));
+                        }
+                        ConnectionType::Connection(node_connection) if node_connection.source_layer >= layer_index || self.layers[node_connection.source_layer].nodes.len() <= node_connection.source_node => {
+                            problems.push(format!("layer {} node {}: connection to missing or later node {} {}", layer_index, node_index, node_connection.source_layer, node_connection.source_node));
+                        }
+                        _ => {}
+                    }
+                }
             }
-            if let Some(maximum_connections) = value.maximum_connections {
-                max = maximum_connections;
-            } else {
-                max = 0;
+        }
+        problems
+    } //Describes every problem that would stop the network from evaluating: unknown node types, connection counts outside a node types limits, inputs past input_count, and connections that don't feed forward
+
+    pub fn constants(&self) -> Vec<f64> {
+        let mut constants = Vec::new();
+        for layer in &self.layers {
+            for node in &layer.nodes {
+                for connection in &node.connections {
+                    if let ConnectionType::Constant(constant) = connection {
+                        constants.push(constant.value);
+                    }
+                }
+            }
+        }
+        constants
+    } //Values of every constant, in layer, node, then connection order
+
+    pub fn set_constants(&mut self, values: &[f64]) -> Result<(), Error> {
+        let mut values = values.iter();
+        for layer in self.layers.iter_mut() {
+            for node in layer.nodes.iter_mut() {
+                for connection in node.connections.iter_mut() {
+                    if let ConnectionType::Constant(constant) = connection {
+                        constant.value = *values.next().ok_or(Error::InvalidConnection)?;
+                    }
+                }
+            }
+        }
+        if values.next().is_some() { //More values than constants
+            return Err(Error::InvalidConnection);
+        }
+        Ok(())
+    } //Overwrites every constant, in the same order as constants() returns them
+
+    pub fn complexity(&self) -> Complexity {
+        let mut connection_count = 0;
+        let mut family_histogram = BTreeMap::new();
+        for layer in &self.layers {
+            for node in &layer.nodes {
+                connection_count += node.connections.len();
+                *family_histogram.entry(node.node_type_id / 100 * 100).or_insert(0) += 1;
             }
-            println!("{} - {}, {}, {}, {}, {}", key, value.name, value.description, max, min, value.prevalence);
         }
-    }
 
-    #[test]
-    fn generate_layer_to_network() {
-        let mut network = super::Network::new();
-        let _ = network.generate_layer_to_network();
-        let _ = network.generate_node_to_layer(201, 0);
-        assert_eq!(format!("{:?}", network), "Network { layers: [Layer { nodes: [Node { node_type_id: 201, connections: [], state: 0.0 }] }], inputs: [], node_selection_table: NodeSelectionTable { node_type_mapping_descriptors: {} } }")
-    }
+        //Walks backwards from the last layer, marking every node that an output depends on
+        let mut reachable: Vec<Vec<bool>> = self.layers.iter().map(|layer| vec![false; layer.nodes.len()]).collect();
+        if let Some(last_layer) = reachable.last_mut() {
+            last_layer.iter_mut().for_each(|node_reachable| *node_reachable = true);
+        }
+        for layer_index in (0..self.layers.len()).rev() {
+            for (node_index, node) in self.layers[layer_index].nodes.iter().enumerate() {
+                if !reachable[layer_index][node_index] {
+                    continue;
+                }
+                for connection in &node.connections {
+                    if let ConnectionType::Connection(node_connection) = connection {
+                        reachable[node_connection.source_layer][node_connection.source_node] = true;
+                    }
+                }
+            }
+        }
 
-    #[test]
-    fn node_tests() {
-        //101
-        assert_eq!(run_node(101, vec![10.0, 20.0, 30.0, 40.0]).unwrap(), 10f64);
-        assert_eq!(run_node(101, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 40f64);
+        //Depth of a node is one more than the deepest node feeding into it, layers are already in dependency order
+        let mut depths: Vec<Vec<usize>> = Vec::new();
+        for layer in &self.layers {
+            let layer_depths = layer.nodes.iter().map(|node| {
+                let mut depth = 0;
+                for connection in &node.connections {
+                    if let ConnectionType::Connection(node_connection) = connection {
+                        depth = depth.max(depths[node_connection.source_layer][node_connection.source_node]);
+                    }
+                }
+                depth + 1
+            }).collect();
+            depths.push(layer_depths);
+        }
 
-        //102
-        assert_eq!(run_node(102, vec![10.0, 20.0, 20.0, 40.0]).unwrap(), 10f64);
-        assert_eq!(run_node(102, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 40f64);
+        Complexity {
+            node_count: self.node_count(),
+            connection_count,
+            depth: depths.last().and_then(|layer_depths| layer_depths.iter().max().copied()).unwrap_or(0),
+            effective_node_count: reachable.iter().flatten().filter(|node_reachable| **node_reachable).count(),
+            family_histogram,
+        }
+    } //Measures the size of the network
+
+    pub fn evaluate(&mut self, inputs: &[f64], rng: &mut Rng) -> Result<Vec<f64>, Error> {
+        let inputs: Vec<Value> = inputs.iter().map(|input| Value::Number(*input)).collect();
+        let outputs = self.evaluate_values(&inputs, rng)?;
+        Ok(outputs.iter().map(Value::as_number).collect())
+    } //Evaluates the network on numeric inputs, returns the states of the nodes in the last layer
+
+    pub fn evaluate_values(&mut self, inputs: &[Value], rng: &mut Rng) -> Result<Vec<Value>, Error> {
+        self.inputs = inputs.iter().map(Value::as_number).collect();
+        let mut layer_values: Vec<Vec<Value>> = Vec::new();
+
+        for layer in self.layers.iter_mut() { //Layers are evaluated in order, nodes only read from earlier layers
+            let mut values = Vec::new();
+            for node in layer.nodes.iter_mut() {
+                let mut node_inputs = Vec::new();
+                for connection in node.connections.iter_mut() {
+                    let value = match connection {
+                        ConnectionType::Connection(node_connection) => {
+                            let value = layer_values.get(node_connection.source_layer)
+                                .and_then(|source_layer| source_layer.get(node_connection.source_node))
+                                .ok_or(Error::InvalidConnection)?;
+                            node_connection.value = value.as_number();
+                            value.clone()
+                        }
+                        ConnectionType::Constant(constant) => Value::Number(constant.value),
+                        ConnectionType::Input(input) => {
+                            let value = inputs.get(input.input_id).ok_or(Error::InvalidInput)?;
+                            input.value = value.as_number();
+                            value.clone()
+                        }
+                    };
+                    node_inputs.push(value);
+                }
+                if node.node_type_id == 608 {
+                    node_inputs = weighted_terms(&node.connections).iter()
+                        .map(|(value, weight)| Value::Number(node_inputs[*value].as_number() * weight.map_or(1.0, |weight| node_inputs[weight].as_number())))
+                        .collect();
+                }
 
-        //103
-        assert_eq!(run_node(103, vec![10.0, 20.0, 20.0, 40.0]).unwrap(), 40f64);
-        assert_eq!(run_node(103, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 10f64);
+                let output = if is_memory_node(node.node_type_id) {
+                    let numbers = node_inputs.iter().map(Value::as_number).collect();
+                    run_memory_node(node.node_type_id, numbers, &mut node.memory).map(Value::Number)
+                } else if is_regex_node(node.node_type_id) {
+                    run_regex_node(node.node_type_id, node_inputs, &mut node.pattern_cache)
+                } else {
+                    run_value_node(node.node_type_id, node_inputs, rng)
+                };
+                let output = output.ok_or(Error::NodeEvaluation)?;
+                node.state = output.as_number();
+                values.push(output);
+            }
+            layer_values.push(values);
+        }
 
-    }
+        Ok(layer_values.pop().unwrap_or_default())
+    } //Evaluates the network on numeric or text inputs, returns the values of the nodes in the last layer
 
-}
+    pub fn reset_state(&mut self) {
+        for node in self.layers.iter_mut().flat_map(|layer| layer.nodes.iter_mut()) {
+            node.state = 0.0;
+            node.memory = 0.0;
+        }
+    } //Starts a new episode, memory nodes forget everything they have seen
 
-fn get_mean_of_lowest_and_highest(inputs: &Vec<f64>) -> (f64, f64, f64) {
-    if inputs.len() < 1 {
-        return (0.0, 0.0, 0.0);
-    }
-    let mut largest_input = *inputs.get(0).unwrap();
-    let mut smallest_input = *inputs.get(0).unwrap();
+    pub fn has_memory(&self) -> bool {
+        self.layers.iter().flat_map(|layer| &layer.nodes).any(|node| is_memory_node(node.node_type_id))
+    } //Whether the outputs can depend on earlier evaluations
 
-    for input in inputs {
-        if *input > largest_input {
-            largest_input = *input;
+    pub fn connect_node(&mut self, layer_index: usize, node_index: usize, source_layer_index: usize, source_node_index: usize) -> Result<(), Error> {
+        if source_layer_index >= layer_index { //Connections can only feed forward, from an earlier layer
+            return Err(Error::InvalidConnection);
         }
-        if *input < smallest_input {
-            smallest_input = *input;
+        if source_layer_index >= self.layers.len() || source_node_index >= self.layers[source_layer_index].nodes.len() {
+            return Err(Error::InvalidConnection);
         }
-    }
+        let node = self.get_node(layer_index, node_index)?;
+        node.connections.push(ConnectionType::Connection(NodeConnection {
+            value: 0.0,
+            source_layer: source_layer_index,
+            source_node: source_node_index,
+        }));
+        Ok(())
+    } //Connects the output of a node in an earlier layer to a node
+
+    pub fn connect_input(&mut self, layer_index: usize, node_index: usize, input_id: usize) -> Result<(), Error> {
+        let node = self.get_node(layer_index, node_index)?;
+        node.connections.push(ConnectionType::Input(Input {
+            value: 0.0,
+            input_id,
+        }));
+        Ok(())
+    } //Connects one of the networks inputs to a node
+
+    pub fn connect_constant(&mut self, layer_index: usize, node_index: usize, value: f64) -> Result<(), Error> {
+        let node = self.get_node(layer_index, node_index)?;
+        node.connections.push(ConnectionType::Constant(Constant {
+            value,
+        }));
+        Ok(())
+    } //Connects a constant value to a node
+
+    /*
+    The structural functions below shift the indices of layers and nodes around, so every one of them finishes by remapping
+    the node connections of the whole network. Connections whose source no longer exists, or whose source ends up in the same
+    or a later layer than the receiving node, are dropped. The functions that can drop connections return the (layer, node)
+    positions of every node that lost a connection and is now under the minimum connections of its node type.
+     */
+    pub fn remove_node(&mut self, layer_index: usize, node_index: usize) -> Result<Vec<(usize, usize)>, Error> {
+        self.get_node(layer_index, node_index)?;
+        self.layers[layer_index].nodes.remove(node_index);
+
+        Ok(self.remap_connections(|source_layer, source_node| {
+            if source_layer != layer_index || source_node < node_index {
+                Some((source_layer, source_node))
+            } else if source_node == node_index {
+                None
+            } else {
+                Some((source_layer, source_node - 1))
+            }
+        }))
+    } //Removes a node, dropping connections that came from it
 
-    return ((smallest_input + largest_input)/2.0, smallest_input, largest_input)
-}
+    pub fn insert_layer_at(&mut self, layer_index: usize) -> Result<&mut Layer, Error> {
+        if layer_index > self.layers.len() { //Inserting at the length is the same as appending
+            return Err(Error::InvalidLayer);
+        }
+        self.layers.insert(layer_index, Layer::new());
+
+        self.remap_connections(|source_layer, source_node| {
+            if source_layer >= layer_index {
+                Some((source_layer + 1, source_node))
+            } else {
+                Some((source_layer, source_node))
+            }
+        });
+        Ok(self.layers.get_mut(layer_index).unwrap())
+    } //Inserts an empty layer, shifting every later layer back by one
+
+    pub fn remove_layer(&mut self, layer_index: usize) -> Result<Vec<(usize, usize)>, Error> {
+        self.get_layer(layer_index)?;
+        self.layers.remove(layer_index);
+
+        Ok(self.remap_connections(|source_layer, source_node| {
+            if source_layer < layer_index {
+                Some((source_layer, source_node))
+            } else if source_layer == layer_index {
+                None
+            } else {
+                Some((source_layer - 1, source_node))
+            }
+        }))
+    } //Removes a layer and all of its nodes, dropping connections that came from them
+
+    pub fn move_node(&mut self, layer_index: usize, node_index: usize, target_layer_index: usize) -> Result<Vec<(usize, usize)>, Error> {
+        self.get_node(layer_index, node_index)?;
+        self.get_layer(target_layer_index)?;
+        let node = self.layers[layer_index].nodes.remove(node_index);
+        self.layers[target_layer_index].nodes.push(node);
+        let new_node_index = self.layers[target_layer_index].nodes.len() - 1;
+
+        Ok(self.remap_connections(|source_layer, source_node| {
+            if source_layer == layer_index && source_node == node_index {
+                Some((target_layer_index, new_node_index))
+            } else if source_layer == layer_index && source_node > node_index {
+                Some((source_layer, source_node - 1))
+            } else {
+                Some((source_layer, source_node))
+            }
+        }))
+    } //Moves a node to the end of another layer, connections that would no longer feed forward are dropped
+
+    pub fn replace_node_type(&mut self, layer_index: usize, node_index: usize, node_type: usize) -> Result<Vec<(usize, usize)>, Error> {
+        let descriptor = match NodeSelectionTable::template_descriptor(node_type) {
+            Some(descriptor) => descriptor,
+            None => return Err(Error::InvalidNodeType),
+        };
+        let node = self.get_node(layer_index, node_index)?;
+        node.node_type_id = node_type;
+        node.memory = 0.0; //Whatever the old type remembered means nothing to the new one
+        if let Some(maximum_connections) = descriptor.maximum_connections {
+            node.connections.truncate(maximum_connections); //Excess connections are dropped from the end
+        }
+
+        let mut under_minimum = Vec::new();
+        if node.connections.len() < descriptor.minimum_connections.unwrap_or(0) {
+            under_minimum.push((layer_index, node_index));
+        }
+        Ok(under_minimum)
+    } //Changes the type of a node while keeping as many of its connections as the new type allows
+
+    fn remap_connections<F>(&mut self, remap: F) -> Vec<(usize, usize)> where F: Fn(usize, usize) -> Option<(usize, usize)> {
+        let mut under_minimum = Vec::new();
+        for (layer_index, layer) in self.layers.iter_mut().enumerate() {
+            for (node_index, node) in layer.nodes.iter_mut().enumerate() {
+                let connection_count = node.connections.len();
+                node.connections.retain_mut(|connection| {
+                    if let ConnectionType::Connection(node_connection) = connection {
+                        match remap(node_connection.source_layer, node_connection.source_node) {
+                            Some((source_layer, source_node)) if source_layer < layer_index => {
+                                node_connection.source_layer = source_layer;
+                                node_connection.source_node = source_node;
+                            }
+                            _ => return false,
+                        }
+                    }
+                    true
+                });
+
+                if node.connections.len() < connection_count {
+                    let minimum_connections = NodeSelectionTable::template_descriptor(node.node_type_id)
+                        .and_then(|descriptor| descriptor.minimum_connections)
+                        .unwrap_or(0);
+                    if node.connections.len() < minimum_connections {
+                        under_minimum.push((layer_index, node_index));
+                    }
+                }
+            }
+        }
+        under_minimum
+    } //Rewrites every node connection through the remap function, returns nodes that lost connections and are under their minimum
+
+}
+
+
+fn get_mean_of_lowest_and_highest(inputs: &[f64]) -> (f64, f64, f64) {
+    if inputs.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let mut largest_input = inputs[0];
+    let mut smallest_input = inputs[0];
+
+    for input in inputs {
+        if *input > largest_input {
+            largest_input = *input;
+        }
+        if *input < smallest_input {
+            smallest_input = *input;
+        }
+    }
+
+    ((smallest_input + largest_input)/2.0, smallest_input, largest_input)
+}
 
 pub fn run_node(node_type: usize, inputs: Vec<f64>) -> Option<f64> {
     match node_type {
@@ -795,7 +1659,7 @@ pub fn run_node(node_type: usize, inputs: Vec<f64>) -> Option<f64> {
                 }
 
             }
-            return Some(largest_input);
+            Some(largest_input)
 
         } //end ADD Node
 
@@ -807,7 +1671,7 @@ pub fn run_node(node_type: usize, inputs: Vec<f64>) -> Option<f64> {
                 }
 
             }
-            return Some(smallest_input);
+            Some(smallest_input)
 
         } //end OR Node
 
@@ -819,14 +1683,11 @@ pub fn run_node(node_type: usize, inputs: Vec<f64>) -> Option<f64> {
                 }
 
             }
-            return Some(largest_input);
+            Some(largest_input)
         } //End NOR Node
 
         104 => { //NOT Node: Since it cannot exactly determine the mapped truth value parameters, it just inverts the number
-            for input in inputs {
-                return Some(input * -1f64);
-            }
-            return None;
+            inputs.first().map(|input| -input)
         } //End NOT Node
 
         105 => { //NAND Node
@@ -837,13 +1698,1061 @@ pub fn run_node(node_type: usize, inputs: Vec<f64>) -> Option<f64> {
                 }
 
             }
-            return Some(smallest_input);
+            Some(smallest_input)
         } //End NAND Node
 
-        106 => { //XOR Node
-
+        106 => { //XOR Node: true when an odd amount of inputs are true, omits least input during comparison
+            let (logic_mean, smallest_input, largest_input) = get_mean_of_lowest_and_highest(&inputs);
+            let mut true_inputs = 0;
+            for input in inputs {
+                if input >= logic_mean && smallest_input != input {
+                    true_inputs += 1;
+                }
+            }
+            if true_inputs % 2 == 1 {
+                return Some(largest_input);
+            }
+            Some(smallest_input)
         } //End XOR Node
+
+        107 => { //Equality Test Node
+            let (_, smallest_input, largest_input) = get_mean_of_lowest_and_highest(&inputs);
+            if inputs.is_empty() {
+                return None;
+            }
+            if smallest_input == largest_input {
+                return Some(largest_input);
+            }
+            Some(smallest_input)
+        } //End Equality Test Node
+
+        108 => { //Equality Test Gate Node
+            gate(&inputs, |first, second| first == second)
+        } //End Equality Test Gate Node
+
+        109 => { //Greater Than Node
+            compare(&inputs, |first, second| first > second)
+        } //End Greater Than Node
+
+        110 => { //Greater Than Gate Node
+            gate(&inputs, |first, second| first > second)
+        } //End Greater Than Gate Node
+
+        111 => { //Less Than Node
+            compare(&inputs, |first, second| first < second)
+        } //End Less Than Node
+
+        112 => { //Less Than Gate Node
+            gate(&inputs, |first, second| first < second)
+        } //End Less Than Gate Node
+
+        113 => { //Passthrough Node
+            inputs.first().copied()
+        } //End Passthrough Node
+
+        114 => { //Greater Than or Equal to Node
+            compare(&inputs, |first, second| first >= second)
+        } //End Greater Than or Equal to Node
+
+        115 => { //Greater Than or Equal to Gate Node
+            gate(&inputs, |first, second| first >= second)
+        } //End Greater Than or Equal to Gate Node
+
+        116 => { //Less Than or Equal to Node
+            compare(&inputs, |first, second| first <= second)
+        } //End Less Than or Equal to Node
+
+        117 => { //Less Than or Equal to Gate Node
+            gate(&inputs, |first, second| first <= second)
+        } //End Less Than or Equal to Gate Node
+
+        /*
+        Arithmetic nodes never return infinities or NaN, anything outside of a functions domain evaluates to 0. Inputs
+        that only make sense inside of a certain range (arcsin, arccos) are clamped to that range first.
+         */
+        201 => { //Add Node
+            if inputs.is_empty() {
+                return None;
+            }
+            Some(finite_or_zero(inputs.iter().sum()))
+        } //End Add Node
+
+        202 => { //Subtract Node
+            let first = *inputs.first()?;
+            Some(finite_or_zero(inputs[1..].iter().fold(first, |total, input| total - input)))
+        } //End Subtract Node
+
+        203 => { //Multiply Node
+            if inputs.is_empty() {
+                return None;
+            }
+            Some(finite_or_zero(inputs.iter().product()))
+        } //End Multiply Node
+
+        204 => { //Divide Node: dividing by zero skips that input
+            let first = *inputs.first()?;
+            let mut total = first;
+            for input in &inputs[1..] {
+                if *input != 0.0 {
+                    total /= input;
+                }
+            }
+            Some(finite_or_zero(total))
+        } //End Divide Node
+
+        205 => { //Root Node: roots of negative numbers keep their sign
+            let (value, root) = (*inputs.first()?, *inputs.get(1)?);
+            if root == 0.0 {
+                return Some(0.0);
+            }
+            Some(finite_or_zero(value.signum() * value.abs().powf(1.0 / root)))
+        } //End Root Node
+
+        206 => { //Exponent Node
+            Some(finite_or_zero(inputs.first()?.powf(*inputs.get(1)?)))
+        } //End Exponent Node
+
+        207 => Some(finite_or_zero(inputs.first()?.sin())), //Sin Node
+        208 => Some(finite_or_zero(inputs.first()?.cos())), //Cos Node
+        209 => Some(finite_or_zero(inputs.first()?.tan())), //Tan Node
+        210 => Some(finite_or_zero(inputs.first()?.clamp(-1.0, 1.0).asin())), //Arcsin Node
+        211 => Some(finite_or_zero(inputs.first()?.clamp(-1.0, 1.0).acos())), //Arccos Node
+        212 => Some(finite_or_zero(inputs.first()?.atan())), //Arctan Node
+        213 => Some(finite_or_zero(inputs.first()?.abs())), //Abs Node
+        214 => Some(finite_or_zero(inputs.first()?.ceil())), //Ceiling Node
+        215 => Some(finite_or_zero(inputs.first()?.floor())), //Floor Node
+        216 => Some(finite_or_zero(inputs.first()?.round())), //Round Node
+        217 => Some(finite_or_zero(-*inputs.first()?)), //Invert Sign Node
+        218 => Some(finite_or_zero(inputs.first()?.min(*inputs.get(1)?))), //Maximum Limiter Node
+        219 => Some(finite_or_zero(inputs.first()?.max(*inputs.get(1)?))), //Minimum Limiter Node
+
+        220 => { //Modulus Node
+            let (value, modulus) = (*inputs.first()?, *inputs.get(1)?);
+            if modulus == 0.0 {
+                return Some(0.0);
+            }
+            Some(finite_or_zero(value % modulus))
+        } //End Modulus Node
+
+        221 => Some(finite_or_zero(-inputs.first()?.abs())), //Make Negative Node
+        222 => Some(finite_or_zero(1.0 / inputs.first()?.sin())), //Cosecant Node
+        223 => Some(finite_or_zero(1.0 / inputs.first()?.cos())), //Secant Node
+        224 => Some(finite_or_zero(1.0 / inputs.first()?.tan())), //Cotangent Node
+        225 => Some(finite_or_zero(inputs.first()?.abs().ln())), //Natural Log Node: logarithms are of the absolute value, the log of 0 is 0
+        226 => Some(finite_or_zero(inputs.first()?.abs().ln() / inputs.get(1)?.abs().ln())), //Logarithm Node: a base of 0 or 1 returns 0
+        227 => Some(finite_or_zero(inputs.first()?.exp())), //Exp Node
+        228 => Some(finite_or_zero(inputs.first()?.sinh())), //Sinh Node
+        229 => Some(finite_or_zero(inputs.first()?.cosh())), //Cosh Node
+        230 => Some(finite_or_zero(inputs.first()?.asinh())), //Arcsinh Node
+        231 => Some(finite_or_zero(inputs.first()?.max(1.0).acosh())), //Arccosh Node
+        232 => Some(finite_or_zero(inputs.first()?.clamp(-1.0, 1.0).atanh())), //Arctanh Node: -1 and 1 themselves return 0
+
+        /*
+        Statistical nodes summarize any amount of inputs into one number, the variance and standard deviation are of the
+        population (divided by the amount of inputs, not one less), so a single input has a variance of 0.
+         */
+        501 => Some(finite_or_zero(mean(&inputs)?)), //Mean Node
+
+        502 => { //Median Node
+            let mut sorted = inputs.clone();
+            sorted.sort_by(f64::total_cmp);
+            let middle = sorted.len() / 2;
+            match sorted.len() {
+                0 => None,
+                length if length % 2 == 0 => Some(finite_or_zero((sorted[middle - 1] + sorted[middle]) / 2.0)),
+                _ => Some(sorted[middle]),
+            }
+        } //End Median Node
+
+        503 => inputs.iter().copied().reduce(f64::min), //Minimum Node
+        504 => inputs.iter().copied().reduce(f64::max), //Maximum Node
+        505 => Some(finite_or_zero(variance(&inputs)?)), //Variance Node
+        506 => Some(finite_or_zero(variance(&inputs)?.sqrt())), //Standard Deviation Node
+
+        507 => Some(finite_or_zero(inputs.iter().copied().reduce(f64::max)? - inputs.iter().copied().reduce(f64::min)?)), //Range Node
+
+        508 => { //Count Above Mean Node
+            let mean = mean(&inputs)?;
+            Some(inputs.iter().filter(|input| **input > mean).count() as f64)
+        } //End Count Above Mean Node
+
+        /*
+        Activation functions, so a network can mix formulas with neuron like behavior. A neuron is a Weighted Sum node
+        (each input connection followed by a constant connection as its weight, optimized like any other constant)
+        feeding one of the activation nodes.
+         */
+        601 => { //Sigmoid Node: written so large inputs of either sign don't overflow
+            let input = *inputs.first()?;
+            if input >= 0.0 {
+                return Some(finite_or_zero(1.0 / (1.0 + (-input).exp())));
+            }
+            Some(finite_or_zero(input.exp() / (1.0 + input.exp())))
+        } //End Sigmoid Node
+
+        602 => Some(finite_or_zero(inputs.first()?.tanh())), //Tanh Node
+        603 => Some(finite_or_zero(inputs.first()?.max(0.0))), //ReLU Node
+
+        604 => { //Leaky ReLU Node
+            let input = *inputs.first()?;
+            if input > 0.0 {
+                return Some(finite_or_zero(input));
+            }
+            Some(finite_or_zero(input * 0.01))
+        } //End Leaky ReLU Node
+
+        605 => { //Softplus Node: max(x, 0) + ln(1 + e^-|x|) is the same value without overflowing
+            let input = *inputs.first()?;
+            Some(finite_or_zero(input.max(0.0) + (-input.abs()).exp().ln_1p()))
+        } //End Softplus Node
+
+        606 => Some(finite_or_zero((-inputs.first()?.powi(2)).exp())), //Gaussian Node
+        607 => Some(if *inputs.first()? >= 0.0 { 1.0 } else { 0.0 }), //Step Node
+
+        608 => { //Weighted Sum Node: the inputs are already weighted, see weighted_terms
+            if inputs.is_empty() {
+                return None;
+            }
+            Some(finite_or_zero(inputs.iter().sum()))
+        } //End Weighted Sum Node
+
+        /*
+        Integer nodes work on 64 bit signed integers made with to_integer, and never fail or panic: shifts are limited
+        to 0 to 63 bits, dividing by zero returns 0 and dividing the least integer by -1 wraps back to the least integer.
+        Results are turned back into numbers, which is exact up to 2^53.
+         */
+        801 => Some(inputs.iter().map(|input| to_integer(*input)).reduce(|total, input| total & input)? as f64), //Bitwise AND Node
+        802 => Some(inputs.iter().map(|input| to_integer(*input)).reduce(|total, input| total | input)? as f64), //Bitwise OR Node
+        803 => Some(inputs.iter().map(|input| to_integer(*input)).reduce(|total, input| total ^ input)? as f64), //Bitwise XOR Node
+        804 => Some(!to_integer(*inputs.first()?) as f64), //Bitwise NOT Node
+        805 => Some(to_integer(*inputs.first()?).wrapping_shl(shift_amount(*inputs.get(1)?)) as f64), //Shift Left Node
+        806 => Some((to_integer(*inputs.first()?) >> shift_amount(*inputs.get(1)?)) as f64), //Shift Right Node
+        807 => Some(to_integer(*inputs.first()?).count_ones() as f64), //Popcount Node
+
+        808 => { //Integer Division Node
+            let (dividend, divisor) = (to_integer(*inputs.first()?), to_integer(*inputs.get(1)?));
+            if divisor == 0 {
+                return Some(0.0);
+            }
+            Some(dividend.wrapping_div(divisor) as f64)
+        } //End Integer Division Node
+
+        809 => Some(to_integer(*inputs.first()?) as f64), //Truncate Node
+
+        903 => extreme_index(&inputs, |input, extreme| input > extreme), //Argmax Node
+        904 => extreme_index(&inputs, |input, extreme| input < extreme), //Argmin Node
         _ => None
     }
 }
 
+fn finite_or_zero(value: f64) -> f64 {
+    if value.is_finite() {
+        return value;
+    }
+    0.0
+}
+
+fn mean(inputs: &[f64]) -> Option<f64> {
+    if inputs.is_empty() {
+        return None;
+    }
+    Some(inputs.iter().sum::<f64>() / inputs.len() as f64)
+}
+
+fn variance(inputs: &[f64]) -> Option<f64> {
+    let mean = mean(inputs)?;
+    Some(inputs.iter().map(|input| (input - mean).powi(2)).sum::<f64>() / inputs.len() as f64)
+} //Population variance
+
+fn extreme_index<F>(inputs: &[f64], is_more_extreme: F) -> Option<f64> where F: Fn(f64, f64) -> bool {
+    inputs.first()?;
+    let mut extreme_index = 0;
+    for (index, input) in inputs.iter().enumerate() {
+        if is_more_extreme(*input, inputs[extreme_index]) || inputs[extreme_index].is_nan() {
+            extreme_index = index;
+        }
+    }
+    Some(extreme_index as f64)
+} //Index of the greatest or least input, NaN inputs are never picked unless every input is NaN
+
+fn to_integer(value: f64) -> i64 {
+    value as i64
+} //Drops the fraction, values past the integer range become the largest or least integer and NaN becomes 0
+
+fn shift_amount(value: f64) -> u32 {
+    to_integer(value).clamp(0, 63) as u32
+}
+
+fn compare<F>(inputs: &[f64], comparison: F) -> Option<f64> where F: Fn(f64, f64) -> bool {
+    let (first, second) = (*inputs.first()?, *inputs.get(1)?);
+    if comparison(first, second) {
+        return Some(first.max(second));
+    }
+    Some(first.min(second))
+} //Compares the first two inputs, returns a mapped truth value
+
+fn gate<F>(inputs: &[f64], comparison: F) -> Option<f64> where F: Fn(f64, f64) -> bool {
+    let (first, second, third) = (*inputs.first()?, *inputs.get(1)?, *inputs.get(2)?);
+    if comparison(first, second) {
+        return Some(third);
+    }
+    Some(0.0)
+} //Compares the first two inputs, returns the third input if true and 0 if false
+
+pub(crate) fn weighted_terms(connections: &[ConnectionType]) -> Vec<(usize, Option<usize>)> {
+    let mut terms = Vec::new();
+    let mut index = 0;
+    while index < connections.len() {
+        let is_constant = |index: usize| matches!(connections.get(index), Some(ConnectionType::Constant(_)));
+        if !is_constant(index) && is_constant(index + 1) {
+            terms.push((index, Some(index + 1)));
+            index += 2;
+        } else {
+            terms.push((index, None));
+            index += 1;
+        }
+    }
+    terms
+} //Terms of a Weighted Sum node as (value, weight) connection indices. Pairing by connection kind instead of position means dropping a connection never turns values into weights
+
+pub fn is_memory_node(node_type: usize) -> bool {
+    (701..=704).contains(&node_type)
+}
+
+pub fn run_memory_node(node_type: usize, inputs: Vec<f64>, memory: &mut f64) -> Option<f64> {
+    /*
+    Memory nodes keep one number between evaluations, so a network can react to sequences instead of single rows. The
+    memory starts at 0 and goes back to 0 with Network::reset_state, which Dataset::evaluate_batch calls before the
+    first row, so every pass over a dataset (an episode) starts from the same state and fitness stays deterministic.
+     */
+    match node_type {
+        701 => { //Delay Node
+            let previous = *memory;
+            *memory = finite_or_zero(*inputs.first()?);
+            Some(previous)
+        } //End Delay Node
+
+        702 => { //Accumulator Node
+            let decay = inputs.get(1).copied().unwrap_or(1.0).clamp(0.0, 1.0);
+            *memory = finite_or_zero(*memory * decay + inputs.first()?);
+            Some(*memory)
+        } //End Accumulator Node
+
+        703 => { //Latch Node: equal inputs keep the latch as it is
+            let (set, reset) = (*inputs.first()?, *inputs.get(1)?);
+            if set > reset {
+                *memory = 1.0;
+            } else if reset > set {
+                *memory = 0.0;
+            }
+            Some(*memory)
+        } //End Latch Node
+
+        704 => { //Counter Node
+            if *inputs.first()? > 0.0 {
+                *memory += 1.0;
+            }
+            if inputs.get(1).is_some_and(|reset| *reset > 0.0) {
+                *memory = 0.0;
+            }
+            Some(*memory)
+        } //End Counter Node
+
+        _ => None
+    }
+} //Runs a memory node, updating what it remembers
+
+pub const MAX_TEXT_LENGTH: usize = 4096; //Nodes that can make text longer than their inputs cut it to this many characters
+
+fn capped_text(text: String) -> Value {
+    if text.len() <= MAX_TEXT_LENGTH {
+        return Value::Text(text); //Never more characters than bytes
+    }
+    Value::Text(text.chars().take(MAX_TEXT_LENGTH).collect())
+} //Keeps chains of Replace and Concatenate nodes from growing text without bound
+
+fn mapped_truth(is_true: bool, outcomes: &[Value]) -> f64 {
+    let true_value = outcomes.first().map_or(1.0, Value::as_number);
+    let false_value = outcomes.get(1).map_or(0.0, Value::as_number);
+    if is_true {
+        return true_value.max(false_value);
+    }
+    true_value.min(false_value)
+} //The greater of the two outcomes (1 and 0 if not given) if true, the lesser if false
+
+pub fn is_regex_node(node_type: usize) -> bool {
+    (316..=318).contains(&node_type)
+}
+
+pub fn run_regex_node(node_type: usize, inputs: Vec<Value>, pattern_cache: &mut PatternCache) -> Option<Value> {
+    /*
+    The pattern is usually a constant, so it is compiled the first time a node sees it and kept in the node until the
+    pattern changes, instead of once per dataset row. Patterns that don't compile (or would compile into something
+    huge) never match, and that is cached too.
+     */
+    let (pattern, subject) = (inputs.first()?.as_text(), inputs.get(1)?.as_text());
+    if pattern_cache.as_ref().is_none_or(|(cached_pattern, _)| *cached_pattern != pattern) {
+        let regex = RegexBuilder::new(&pattern).size_limit(1 << 20).build().ok();
+        *pattern_cache = Some((pattern, regex));
+    }
+    let regex = pattern_cache.as_ref().and_then(|(_, regex)| regex.as_ref());
+
+    match node_type {
+        316 => Some(Value::Number(mapped_truth(regex.is_some_and(|regex| regex.is_match(&subject)), &inputs[2..]))), //Regex Match Node
+        317 => Some(Value::Number(regex.map_or(0, |regex| regex.find_iter(&subject).count()) as f64)), //Regex Count Node
+
+        318 => { //Regex Capture Node
+            let captures = regex.and_then(|regex| regex.captures(&subject));
+            let group = if regex.is_some_and(|regex| regex.captures_len() > 1) { 1 } else { 0 }; //The whole match only when there are no groups
+            let capture = captures.and_then(|captures| captures.get(group));
+            Some(Value::Text(capture.map_or("", |capture| capture.as_str()).to_string()))
+        } //End Regex Capture Node
+
+        _ => None
+    }
+} //Runs a regex node, compiling the pattern only when it differs from the cached one
+
+pub fn run_value_node(node_type: usize, inputs: Vec<Value>, rng: &mut Rng) -> Option<Value> {
+    match node_type {
+        316..=318 => run_regex_node(node_type, inputs, &mut None), //Without a node to cache in, the pattern is compiled every time
+
+        /*
+        The conditional nodes return one of their inputs unchanged, so they can pick between texts as well as numbers.
+         */
+        901 => { //If Then Else Node
+            let condition = inputs.first()?.as_number();
+            let (then_value, else_value) = (inputs.get(1)?, inputs.get(2)?);
+            if condition > 0.0 {
+                return Some(then_value.clone());
+            }
+            Some(else_value.clone())
+        } //End If Then Else Node
+
+        902 => { //Switch Node
+            let selector = finite_or_zero(inputs.first()?.as_number().round());
+            let choice_count = inputs.len().checked_sub(1).filter(|count| *count > 0)?;
+            let index = selector.clamp(0.0, (choice_count - 1) as f64) as usize;
+            Some(inputs[index + 1].clone())
+        } //End Switch Node
+
+        905 => { //If Greater Node: compares two inputs directly, for conditions that are not truth values
+            let (first, second) = (inputs.first()?.as_number(), inputs.get(1)?.as_number());
+            let (then_value, else_value) = (inputs.get(2)?, inputs.get(3)?);
+            if first > second {
+                return Some(then_value.clone());
+            }
+            Some(else_value.clone())
+        } //End If Greater Node
+
+        301 => { //String Remove Node
+            let (text, pattern) = (inputs.first()?.as_text(), inputs.get(1)?.as_text());
+            if pattern.is_empty() {
+                return Some(Value::Text(text));
+            }
+            Some(Value::Text(text.replace(&pattern, "")))
+        } //End String Remove Node
+
+        302 => { //String Replace Node
+            let (text, pattern, replacement) = (inputs.first()?.as_text(), inputs.get(1)?.as_text(), inputs.get(2)?.as_text());
+            if pattern.is_empty() {
+                return Some(Value::Text(text));
+            }
+            Some(capped_text(text.replace(&pattern, &replacement)))
+        } //End String Replace Node
+
+        303 => { //String Count Node
+            let (text, pattern) = (inputs.first()?.as_text(), inputs.get(1)?.as_text());
+            if pattern.is_empty() {
+                return Some(Value::Number(0.0));
+            }
+            Some(Value::Number(text.matches(&pattern).count() as f64))
+        } //End String Count Node
+
+        304 => { //Concatenate Node
+            if inputs.is_empty() {
+                return None;
+            }
+            Some(capped_text(inputs.iter().map(Value::as_text).collect()))
+        } //End Concatenate Node
+
+        305 => Some(Value::Number(inputs.first()?.as_text().chars().count() as f64)), //String Length Node
+
+        306 => { //Substring Node: positions are in characters, a negative start or length counts as 0
+            let text = inputs.first()?.as_text();
+            let (start, length) = (inputs.get(1)?.as_number(), inputs.get(2)?.as_number());
+            Some(Value::Text(text.chars().skip(start.max(0.0) as usize).take(length.max(0.0) as usize).collect()))
+        } //End Substring Node
+
+        307 => Some(capped_text(inputs.first()?.as_text().to_uppercase())), //Uppercase Node
+        308 => Some(capped_text(inputs.first()?.as_text().to_lowercase())), //Lowercase Node
+
+        309 => { //String Contains Node
+            let (text, pattern) = (inputs.first()?.as_text(), inputs.get(1)?.as_text());
+            Some(Value::Number(mapped_truth(text.contains(&pattern), &inputs[2..])))
+        } //End String Contains Node
+
+        310 => { //Index Of Node
+            let (text, pattern) = (inputs.first()?.as_text(), inputs.get(1)?.as_text());
+            match text.find(&pattern) {
+                Some(byte_index) => Some(Value::Number(text[..byte_index].chars().count() as f64)),
+                None => Some(Value::Number(-1.0)),
+            }
+        } //End Index Of Node
+
+        311 => { //Split Nth Node: an empty separator doesn't split
+            let (text, separator, index) = (inputs.first()?.as_text(), inputs.get(1)?.as_text(), inputs.get(2)?.as_number());
+            if index < 0.0 {
+                return Some(Value::Text(String::new()));
+            }
+            let part = if separator.is_empty() {
+                Some(text.as_str()).filter(|_| index < 1.0)
+            } else {
+                text.split(separator.as_str()).nth(index as usize)
+            };
+            Some(Value::Text(part.unwrap_or_default().to_string()))
+        } //End Split Nth Node
+
+        312 => Some(Value::Text(inputs.first()?.as_text().trim().to_string())), //Trim Node
+        313 => Some(Value::Text(inputs.first()?.as_text().chars().rev().collect())), //Reverse Node
+        314 => Some(Value::Number(finite_or_zero(inputs.first()?.as_text().trim().parse().unwrap_or(0.0)))), //Parse Number Node
+
+        315 => { //Number To String Node: decimals are limited to 0 to 10
+            let number = inputs.first()?.as_number();
+            match inputs.get(1) {
+                Some(decimals) => Some(Value::Text(format!("{:.*}", decimals.as_number().clamp(0.0, 10.0) as usize, number))),
+                None => Some(Value::Text(number.to_string())),
+            }
+        } //End Number To String Node
+
+        401 => { //Random Number Node: uniform integer between the first two inputs, both ends included
+            let (first, second) = (inputs.first()?.as_number(), inputs.get(1)?.as_number());
+            let low = first.min(second).ceil();
+            let high = first.max(second).floor();
+            if !low.is_finite() || !high.is_finite() {
+                return Some(Value::Number(finite_or_zero(low)));
+            }
+            if low > high {
+                return Some(Value::Number(low));
+            }
+            let drawn = if high - low < u64::MAX as f64 {
+                low + (rng.next_u64() % ((high - low) as u64 + 1)) as f64
+            } else {
+                low + (rng.next_f64() * (high - low + 1.0)).floor() //Too wide to count in a u64, draw a float instead
+            };
+            Some(Value::Number(drawn.min(high)))
+        } //End Random Number Node
+
+        /*
+        Every random node draws from the Rng given to the evaluation (the trainers seeded Rng while training), so a
+        run with the same seed makes the same draws.
+         */
+        402 => { //Gaussian Random Node: a negative standard deviation counts as positive
+            let (mean, standard_deviation) = (inputs.first()?.as_number(), inputs.get(1)?.as_number());
+            Some(Value::Number(finite_or_zero(mean + standard_deviation.abs() * rng.next_gaussian())))
+        } //End Gaussian Random Node
+
+        403 => { //Uniform Random Node
+            let (first, second) = (inputs.first()?.as_number(), inputs.get(1)?.as_number());
+            Some(Value::Number(finite_or_zero(first + (second - first) * rng.next_f64())))
+        } //End Uniform Random Node
+
+        404 => { //Bernoulli Node
+            let probability = finite_or_zero(inputs.first()?.as_number()).clamp(0.0, 1.0);
+            Some(Value::Number(mapped_truth(rng.next_f64() < probability, &inputs[1..])))
+        } //End Bernoulli Node
+
+        405 => { //Random Choice Node
+            if inputs.is_empty() {
+                return None;
+            }
+            Some(inputs[rng.range(0, inputs.len())].clone())
+        } //End Random Choice Node
+
+        _ => {
+            let numbers = inputs.iter().map(Value::as_number).collect();
+            run_node(node_type, numbers).map(Value::Number)
+        }
+    }
+} //Runs any node type, including the ones that work on text or need randomness
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use crate::core_models::dannn::{run_node, run_value_node};
+
+    //DANNN Core Model Tests
+    #[test]
+    fn initialize() { //init test
+        let node_selection_table = super::NodeSelectionTable::new();
+        assert!(node_selection_table.node_type_mapping_descriptors.is_empty());
+    }
+
+    #[test]
+    fn node_selection_table_test() {
+        let mut table = super::NodeSelectionTable::new();
+        table.enable_all();
+        for (key, value) in &table.node_type_mapping_descriptors {
+            println!("{} - {}, {}", key, value.name, value.description);
+        }
+    }
+
+    #[test]
+    fn descriptor_enabling_by_id_test() {
+        let mut table = super::NodeSelectionTable::new();
+        table.enable_by_id(201);
+        for (key, value) in &table.node_type_mapping_descriptors {
+            let min = value.minimum_connections.unwrap_or(0);
+            let max = value.maximum_connections.unwrap_or(0);
+            println!("{} - {}, {}, {}, {}, {}", key, value.name, value.description, max, min, value.prevalence);
+        }
+    }
+
+    #[test]
+    fn generate_layer_to_network() {
+        let mut network = super::Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(201, 0);
+        assert_eq!(format!("{:?}", network), "Network { layers: [Layer { nodes: [Node { node_type_id: 201, connections: [], state: 0.0, memory: 0.0, pattern_cache: None }] }], inputs: [], node_selection_table: NodeSelectionTable { node_type_mapping_descriptors: {} } }")
+    }
+
+    fn build_structural_test_network() -> super::Network {
+        //Layer 0: two passthrough nodes fed by inputs, Layer 1: an add node and a greater than node fed by both
+        let mut network = super::Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(113, 0);
+        let _ = network.generate_node_to_layer(113, 0);
+        let _ = network.generate_node_to_layer(201, 1);
+        let _ = network.generate_node_to_layer(109, 1);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_input(0, 1, 1).unwrap();
+        for node_index in 0..2 {
+            network.connect_node(1, node_index, 0, 0).unwrap();
+            network.connect_node(1, node_index, 0, 1).unwrap();
+        }
+        network
+    }
+
+    fn node_sources(network: &mut super::Network, layer_index: usize, node_index: usize) -> Vec<(usize, usize)> {
+        let mut sources = Vec::new();
+        for connection in &network.get_node(layer_index, node_index).unwrap().connections {
+            if let super::ConnectionType::Connection(node_connection) = connection {
+                sources.push((node_connection.source_layer, node_connection.source_node));
+            }
+        }
+        sources
+    }
+
+    #[test]
+    fn remove_node_remaps_connections() {
+        let mut network = build_structural_test_network();
+        let under_minimum = network.remove_node(0, 0).unwrap();
+        assert_eq!(node_sources(&mut network, 1, 0), vec![(0, 0)]);
+        assert_eq!(node_sources(&mut network, 1, 1), vec![(0, 0)]);
+        assert_eq!(under_minimum, vec![(1, 1)]); //Greater Than needs 2 inputs, Add only needs 1
+        assert!(network.remove_node(0, 5).is_err());
+    }
+
+    #[test]
+    fn insert_and_remove_layer_remaps_connections() {
+        let mut network = build_structural_test_network();
+        network.insert_layer_at(1).unwrap();
+        assert_eq!(network.layers.len(), 3);
+        assert_eq!(node_sources(&mut network, 2, 0), vec![(0, 0), (0, 1)]);
+
+        network.insert_layer_at(0).unwrap();
+        assert_eq!(node_sources(&mut network, 3, 1), vec![(1, 0), (1, 1)]);
+
+        let under_minimum = network.remove_layer(1).unwrap();
+        assert_eq!(under_minimum, vec![(2, 0), (2, 1)]);
+        assert!(node_sources(&mut network, 2, 0).is_empty());
+        assert!(network.insert_layer_at(10).is_err());
+    }
+
+    #[test]
+    fn move_node_drops_connections_that_no_longer_feed_forward() {
+        let mut network = build_structural_test_network();
+        let under_minimum = network.move_node(0, 1, 1).unwrap();
+        assert_eq!(network.layers[0].nodes.len(), 1);
+        assert_eq!(network.layers[1].nodes.len(), 3);
+        assert_eq!(node_sources(&mut network, 1, 0), vec![(0, 0)]); //Connection to the moved node is dropped
+        assert_eq!(under_minimum, vec![(1, 1)]);
+
+        let mut network = build_structural_test_network();
+        network.move_node(1, 0, 0).unwrap();
+        assert_eq!(network.layers[0].nodes[2].node_type_id, 201);
+        assert!(node_sources(&mut network, 0, 2).is_empty()); //Its own connections came from its new layer
+    }
+
+    #[test]
+    fn replace_node_type_respects_arity() {
+        let mut network = build_structural_test_network();
+        assert_eq!(network.replace_node_type(1, 0, 104).unwrap(), vec![]); //NOT takes a single input
+        assert_eq!(network.get_node(1, 0).unwrap().connections.len(), 1);
+        assert_eq!(network.replace_node_type(1, 0, 108).unwrap(), vec![(1, 0)]); //Equality gate needs 3 inputs
+        assert!(network.replace_node_type(1, 0, 9999).is_err());
+    }
+
+    #[test]
+    fn complexity_metrics() {
+        let mut network = build_structural_test_network();
+        let _ = network.generate_node_to_layer(201, 0); //Unused node, not part of the effective node count
+        network.connect_constant(0, 2, 1.0).unwrap();
+        let complexity = network.complexity();
+        assert_eq!(complexity.node_count, 5);
+        assert_eq!(complexity.connection_count, 7);
+        assert_eq!(complexity.depth, 2);
+        assert_eq!(complexity.effective_node_count, 4);
+        assert_eq!(complexity.family_histogram, super::BTreeMap::from([(100, 3), (200, 2)]));
+        assert_eq!(super::Network::new().complexity().depth, 0);
+    }
+
+    #[test]
+    fn network_summary_and_validation() {
+        let mut network = build_structural_test_network();
+        assert_eq!(network.layer_sizes(), vec![2, 2]);
+        assert_eq!(network.output_count(), 2);
+        assert_eq!(network.used_inputs().into_iter().collect::<Vec<usize>>(), vec![0, 1]);
+        assert_eq!(network.node_type_counts().into_iter().collect::<Vec<(usize, usize)>>(), vec![(109, 1), (113, 2), (201, 1)]);
+        assert_eq!(super::NodeSelectionTable::node_type_name(201), Some(String::from("Add")));
+
+        assert!(network.validate(Some(2)).is_empty());
+        assert_eq!(network.validate(Some(1)), vec!["layer 0 node 1: reads input 1 of 1"]);
+        network.layers[1].nodes[1].connections.pop(); //Greater Than needs two connections
+        network.layers[0].nodes[0].node_type_id = 999;
+        assert_eq!(network.validate(None), vec!["layer 0 node 0: unknown node type 999", "layer 1 node 1: Greater Than has 1 connections"]);
+    }
+
+    #[test]
+    fn get_and_set_constants() {
+        let mut network = build_structural_test_network();
+        network.connect_constant(1, 1, 2.0).unwrap();
+        network.connect_constant(0, 0, 1.0).unwrap();
+        assert_eq!(network.constants(), vec![1.0, 2.0]);
+        network.set_constants(&[3.0, 4.0]).unwrap();
+        assert_eq!(network.constants(), vec![3.0, 4.0]);
+        assert!(network.set_constants(&[3.0]).is_err());
+        assert!(network.set_constants(&[3.0, 4.0, 5.0]).is_err());
+    }
+
+    #[test]
+    fn catalog_ids_and_names_are_unique() {
+        use std::collections::BTreeSet;
+        let ids: BTreeSet<usize> = super::NODE_CATALOG.iter().map(|(id, _)| *id).collect();
+        let names: BTreeSet<String> = super::NODE_CATALOG.iter().map(|(_, descriptor)| descriptor.name.to_lowercase()).collect();
+        assert_eq!(ids.len(), super::NODE_CATALOG.len());
+        assert_eq!(names.len(), super::NODE_CATALOG.len());
+        assert!(super::NODE_CATALOG.windows(2).all(|pair| pair[0].0 < pair[1].0)); //Kept in id order
+
+        let mut table = super::NodeSelectionTable::new();
+        table.enable_all();
+        assert_eq!(table.enabled_ids(), ids.into_iter().collect::<Vec<usize>>());
+        table.disable_all();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn table_queries() {
+        let mut table = super::NodeSelectionTable::new();
+        assert!(table.is_empty());
+        table.enable_logic();
+        table.enable_by_id(204);
+        table.set_prevalence_by_id(204, 0.5).unwrap();
+
+        assert_eq!(table.len(), 18);
+        assert!(table.is_enabled(113) && !table.is_enabled(201));
+        assert_eq!(table.enabled_ids()[..3], [101, 102, 103]);
+        assert_eq!(*table.enabled_ids().last().unwrap(), 204);
+
+        let divide = table.descriptor(204).unwrap();
+        assert_eq!((divide.name(), divide.family(), divide.prevalence()), ("Divide", 200, 0.5));
+        assert!(table.descriptor(201).is_none());
+        assert_eq!(table.find_by_name("equality test").iter().map(|descriptor| descriptor.id()).collect::<Vec<usize>>(), vec![107]);
+        assert_eq!(table.filter_by_family(200).len(), 1);
+        assert_eq!(table.filter_by_family(100).len(), 17);
+        assert!(table.filter_by_arity(1).iter().all(|descriptor| descriptor.accepts(1) && descriptor.minimum_connections().unwrap_or(0) <= 1));
+        assert!(table.filter_by_arity(3).iter().any(|descriptor| descriptor.id() == 108)); //Gates take three inputs
+        assert!(!table.filter_by_arity(3).iter().any(|descriptor| descriptor.id() == 104));
+    }
+
+    #[test]
+    fn prevalence_by_id() {
+        let mut table = super::NodeSelectionTable::new();
+        table.enable_simple_arithmetic();
+        table.set_prevalence_by_id(201, 0.25).unwrap();
+        assert_eq!(table.get_prevalence_by_id(201).unwrap(), 0.25);
+        assert!(table.set_prevalence_by_id(201, 1.5).is_err());
+        assert!(table.set_prevalence_by_id(101, 0.5).is_err());
+        assert!(table.get_prevalence_by_id(101).is_err());
+    }
+
+    #[test]
+    fn node_tests() {
+        //101
+        assert_eq!(run_node(101, vec![10.0, 20.0, 30.0, 40.0]).unwrap(), 10f64);
+        assert_eq!(run_node(101, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 40f64);
+
+        //102
+        assert_eq!(run_node(102, vec![10.0, 20.0, 20.0, 40.0]).unwrap(), 10f64);
+        assert_eq!(run_node(102, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 40f64);
+
+        //103
+        assert_eq!(run_node(103, vec![10.0, 20.0, 20.0, 40.0]).unwrap(), 40f64);
+        assert_eq!(run_node(103, vec![10.0, 30.0, 30.0, 40.0]).unwrap(), 10f64);
+
+        //106
+        assert_eq!(run_node(106, vec![10.0, 30.0, 40.0]).unwrap(), 10f64);
+        assert_eq!(run_node(106, vec![10.0, 20.0, 40.0]).unwrap(), 40f64);
+
+        //108, 109
+        assert_eq!(run_node(108, vec![2.0, 2.0, 7.0]).unwrap(), 7f64);
+        assert_eq!(run_node(108, vec![2.0, 3.0, 7.0]).unwrap(), 0f64);
+        assert_eq!(run_node(109, vec![5.0, 3.0]).unwrap(), 5f64);
+        assert_eq!(run_node(109, vec![3.0, 5.0]).unwrap(), 3f64);
+        assert_eq!(run_node(109, vec![3.0]), None);
+
+    }
+
+    #[test]
+    fn integer_nodes() {
+        assert_eq!(run_node(801, vec![12.0, 10.0]).unwrap(), 8f64);
+        assert_eq!(run_node(802, vec![12.0, 10.0, 1.0]).unwrap(), 15f64);
+        assert_eq!(run_node(803, vec![12.0, 10.7]).unwrap(), 6f64); //10.7 is truncated to 10
+        assert_eq!(run_node(804, vec![0.0]).unwrap(), -1f64);
+        assert_eq!(run_node(805, vec![3.0, 2.0]).unwrap(), 12f64);
+        assert_eq!(run_node(805, vec![1.0, 64.0]).unwrap(), i64::MIN as f64); //Limited to 63 bits
+        assert_eq!(run_node(806, vec![-8.0, 1.0]).unwrap(), -4f64);
+        assert_eq!(run_node(806, vec![8.0, -1.0]).unwrap(), 8f64);
+        assert_eq!(run_node(807, vec![7.0]).unwrap(), 3f64);
+        assert_eq!(run_node(807, vec![-1.0]).unwrap(), 64f64);
+        assert_eq!(run_node(808, vec![-7.0, 2.0]).unwrap(), -3f64);
+        assert_eq!(run_node(808, vec![7.0, 0.5]).unwrap(), 0f64); //0.5 is truncated to 0
+        assert_eq!(run_node(808, vec![f64::MIN, -1.0]).unwrap(), i64::MIN as f64);
+        assert_eq!(run_node(809, vec![-2.9]).unwrap(), -2f64);
+        assert_eq!(run_node(809, vec![f64::NAN]).unwrap(), 0f64);
+        assert_eq!(run_node(809, vec![1e300]).unwrap(), i64::MAX as f64);
+    }
+
+    #[test]
+    fn conditional_nodes() {
+        let mut rng = super::Rng::new(1);
+        let numbers = |numbers: &[f64]| numbers.iter().map(|number| super::Value::Number(*number)).collect::<Vec<super::Value>>();
+        assert_eq!(run_value_node(901, numbers(&[1.0, 5.0, 6.0]), &mut rng), Some(super::Value::Number(5.0)));
+        assert_eq!(run_value_node(901, numbers(&[0.0, 5.0, 6.0]), &mut rng), Some(super::Value::Number(6.0)));
+        assert_eq!(run_value_node(901, vec![super::Value::Number(-1.0), super::Value::Text(String::from("yes")), super::Value::Text(String::from("no"))], &mut rng), Some(super::Value::Text(String::from("no"))));
+        let greater_than = |first: f64, second: f64| run_node(110, vec![first, second, 1.0]).unwrap(); //The gate passes 1 if true and 0 if false
+        assert_eq!(run_value_node(901, numbers(&[greater_than(3.0, 5.0), 4.0, 2.0]), &mut rng), Some(super::Value::Number(2.0)));
+        assert_eq!(run_value_node(901, numbers(&[greater_than(5.0, 3.0), 4.0, 2.0]), &mut rng), Some(super::Value::Number(4.0)));
+        assert_eq!(run_value_node(905, numbers(&[2.0, 1.0, 5.0, 6.0]), &mut rng), Some(super::Value::Number(5.0)));
+        assert_eq!(run_value_node(905, numbers(&[1.0, 1.0, 5.0, 6.0]), &mut rng), Some(super::Value::Number(6.0)));
+        assert_eq!(run_value_node(905, numbers(&[1.0, 1.0, 5.0]), &mut rng), None);
+        assert_eq!(run_value_node(902, numbers(&[1.4, 10.0, 20.0, 30.0]), &mut rng), Some(super::Value::Number(20.0)));
+        assert_eq!(run_value_node(902, numbers(&[-3.0, 10.0, 20.0, 30.0]), &mut rng), Some(super::Value::Number(10.0)));
+        assert_eq!(run_value_node(902, numbers(&[7.0, 10.0, 20.0, 30.0]), &mut rng), Some(super::Value::Number(30.0)));
+        assert_eq!(run_value_node(902, numbers(&[0.0]), &mut rng), None);
+        assert_eq!(run_node(903, vec![3.0, 9.0, 1.0, 9.0]).unwrap(), 1f64);
+        assert_eq!(run_node(904, vec![f64::NAN, 3.0, 1.0, 9.0]).unwrap(), 2f64);
+        assert_eq!(run_node(903, Vec::new()), None);
+    }
+
+    #[test]
+    fn arithmetic_node_domains() {
+        assert_eq!(run_node(202, vec![10.0, 3.0, 2.0]).unwrap(), 5f64);
+        assert_eq!(run_node(204, vec![12.0, 0.0, 3.0]).unwrap(), 4f64); //Dividing by zero skips that input
+        assert_eq!(run_node(205, vec![-8.0, 3.0]).unwrap(), -2f64);
+        assert_eq!(run_node(205, vec![8.0, 0.0]).unwrap(), 0f64);
+        assert_eq!(run_node(206, vec![0.0, -1.0]).unwrap(), 0f64);
+        assert_eq!(run_node(210, vec![2.0]).unwrap(), std::f64::consts::FRAC_PI_2);
+        assert_eq!(run_node(220, vec![7.0, 0.0]).unwrap(), 0f64);
+        assert_eq!(run_node(224, vec![0.0]).unwrap(), 0f64);
+        assert_eq!(run_node(225, vec![-std::f64::consts::E]).unwrap(), 1f64);
+        assert_eq!(run_node(225, vec![0.0]).unwrap(), 0f64);
+        assert!((run_node(226, vec![1000.0, 10.0]).unwrap() - 3.0).abs() < 1e-12);
+        assert_eq!(run_node(226, vec![8.0, 1.0]).unwrap(), 0f64);
+        assert_eq!(run_node(227, vec![1000.0]).unwrap(), 0f64); //Overflows
+        assert_eq!(run_node(229, vec![0.0]).unwrap(), 1f64);
+        assert_eq!(run_node(231, vec![-3.0]).unwrap(), 0f64);
+        assert_eq!(run_node(232, vec![2.0]).unwrap(), 0f64);
+        assert!((run_node(230, vec![run_node(228, vec![0.7]).unwrap()]).unwrap() - 0.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn statistical_nodes() {
+        let inputs = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(run_node(501, inputs.clone()).unwrap(), 5f64);
+        assert_eq!(run_node(502, inputs.clone()).unwrap(), 4.5f64);
+        assert_eq!(run_node(502, vec![3.0, -1.0, 2.0]).unwrap(), 2f64);
+        assert_eq!(run_node(503, inputs.clone()).unwrap(), 2f64);
+        assert_eq!(run_node(504, inputs.clone()).unwrap(), 9f64);
+        assert_eq!(run_node(505, inputs.clone()).unwrap(), 4f64);
+        assert_eq!(run_node(506, inputs.clone()).unwrap(), 2f64);
+        assert_eq!(run_node(507, inputs.clone()).unwrap(), 7f64);
+        assert_eq!(run_node(508, inputs).unwrap(), 2f64);
+        assert_eq!(run_node(505, vec![3.0]).unwrap(), 0f64);
+        for node_type in 501..=508 {
+            assert!(run_node(node_type, Vec::new()).is_none());
+        }
+    }
+
+    #[test]
+    fn activation_nodes() {
+        assert_eq!(run_node(601, vec![0.0]).unwrap(), 0.5f64);
+        assert!(run_node(601, vec![-1000.0]).unwrap() == 0.0 && run_node(601, vec![1000.0]).unwrap() == 1.0);
+        assert_eq!(run_node(602, vec![1000.0]).unwrap(), 1f64);
+        assert_eq!(run_node(603, vec![-2.0]).unwrap(), 0f64);
+        assert_eq!(run_node(603, vec![2.0]).unwrap(), 2f64);
+        assert_eq!(run_node(604, vec![-2.0]).unwrap(), -0.02f64);
+        assert!((run_node(605, vec![0.0]).unwrap() - 2f64.ln()).abs() < 1e-12);
+        assert_eq!(run_node(605, vec![1000.0]).unwrap(), 1000f64);
+        assert_eq!(run_node(606, vec![0.0]).unwrap(), 1f64);
+        assert_eq!(run_node(607, vec![0.0]).unwrap(), 1f64);
+        assert_eq!(run_node(607, vec![-0.5]).unwrap(), 0f64);
+        assert!(run_node(601, vec![f64::NAN]).unwrap() == 0.0);
+
+        let mut network = super::Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(608, 0);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_constant(0, 0, 0.5).unwrap();
+        network.connect_input(0, 0, 1).unwrap();
+        network.connect_constant(0, 0, -1.0).unwrap();
+        network.connect_constant(0, 0, 2.0).unwrap(); //Bias
+        network.connect_input(0, 0, 0).unwrap(); //No weight
+        let mut rng = super::Rng::new(1);
+        assert_eq!(network.evaluate(&[2.0, 3.0], &mut rng).unwrap(), vec![2.0]);
+        network.layers[0].nodes[0].connections.remove(0); //0.5 becomes a bias, the other weights stay with their inputs
+        assert_eq!(network.evaluate(&[2.0, 3.0], &mut rng).unwrap(), vec![1.5]);
+    }
+
+    #[test]
+    fn memory_nodes() {
+        let mut memory = 0.0;
+        assert_eq!(super::run_memory_node(701, vec![3.0], &mut memory), Some(0.0));
+        assert_eq!(super::run_memory_node(701, vec![4.0], &mut memory), Some(3.0));
+
+        let mut memory = 0.0;
+        assert_eq!(super::run_memory_node(702, vec![2.0], &mut memory), Some(2.0));
+        assert_eq!(super::run_memory_node(702, vec![2.0, 0.5], &mut memory), Some(3.0));
+
+        let mut memory = 0.0;
+        let latch: Vec<f64> = [(1.0, 0.0), (0.0, 0.0), (0.0, 1.0), (2.0, 2.0)].iter().map(|(set, reset)| super::run_memory_node(703, vec![*set, *reset], &mut memory).unwrap()).collect();
+        assert_eq!(latch, vec![1.0, 1.0, 0.0, 0.0]);
+
+        let mut memory = 0.0;
+        let counter: Vec<f64> = [(1.0, 0.0), (-1.0, 0.0), (1.0, 0.0), (1.0, 1.0)].iter().map(|(count, reset)| super::run_memory_node(704, vec![*count, *reset], &mut memory).unwrap()).collect();
+        assert_eq!(counter, vec![1.0, 1.0, 2.0, 0.0]);
+
+        let mut network = super::Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(702, 0);
+        network.connect_input(0, 0, 0).unwrap();
+        let mut rng = super::Rng::new(1);
+        assert!(network.has_memory());
+        assert_eq!(network.evaluate(&[1.0], &mut rng).unwrap(), vec![1.0]);
+        assert_eq!(network.evaluate(&[1.0], &mut rng).unwrap(), vec![2.0]);
+        network.reset_state();
+        assert_eq!(network.evaluate(&[1.0], &mut rng).unwrap(), vec![1.0]);
+        network.replace_node_type(0, 0, 701).unwrap(); //Forgets the sum
+        assert_eq!(network.evaluate(&[5.0], &mut rng).unwrap(), vec![0.0]);
+    }
+
+    #[test]
+    fn string_and_random_nodes() {
+        let mut rng = super::Rng::new(1);
+        let text = |text: &str| super::Value::Text(String::from(text));
+        assert_eq!(run_value_node(301, vec![text("banana"), text("an")], &mut rng), Some(text("ba")));
+        assert_eq!(run_value_node(302, vec![text("banana"), text("a"), text("o")], &mut rng), Some(text("bonono")));
+        assert_eq!(run_value_node(303, vec![text("banana"), text("a")], &mut rng), Some(super::Value::Number(3.0)));
+        assert_eq!(run_value_node(304, vec![text("ab"), super::Value::Number(2.0)], &mut rng), Some(text("ab2")));
+        assert_eq!(run_value_node(304, vec![text(&"ab".repeat(super::MAX_TEXT_LENGTH)), text("c")], &mut rng).unwrap().as_text().len(), super::MAX_TEXT_LENGTH);
+        for _ in 0..20 {
+            let random = run_value_node(401, vec![super::Value::Number(5.0), super::Value::Number(1.0)], &mut rng).unwrap().as_number();
+            assert!((1.0..=5.0).contains(&random) && random.fract() == 0.0);
+        }
+        for (first, second) in [(-1e20, 1e20), (-1e300, 1e300), (0.0, 1.8e19)] {
+            let random = run_value_node(401, vec![super::Value::Number(first), super::Value::Number(second)], &mut rng).unwrap().as_number();
+            assert!((first..=second).contains(&random) && random.fract() == 0.0);
+        }
+        for (first, second) in [(f64::NEG_INFINITY, 1.0), (f64::INFINITY, f64::INFINITY)] {
+            assert_eq!(run_value_node(401, vec![super::Value::Number(first), super::Value::Number(second)], &mut rng), Some(super::Value::Number(0.0)));
+        }
+    }
+
+    #[test]
+    fn stochastic_nodes() {
+        let number = super::Value::Number;
+        let draws = |node_type: usize, inputs: Vec<super::Value>, seed: u64| -> Vec<super::Value> {
+            let mut rng = super::Rng::new(seed);
+            (0..2000).map(|_| run_value_node(node_type, inputs.clone(), &mut rng).unwrap()).collect()
+        };
+        let mean = |values: &[super::Value]| values.iter().map(super::Value::as_number).sum::<f64>() / values.len() as f64;
+
+        let gaussian = draws(402, vec![number(10.0), number(-2.0)], 1);
+        assert_eq!(gaussian, draws(402, vec![number(10.0), number(-2.0)], 1)); //Same seed, same draws
+        assert!((mean(&gaussian) - 10.0).abs() < 0.2);
+        let uniform = draws(403, vec![number(3.0), number(1.0)], 2);
+        assert!(uniform.iter().all(|value| (1.0..=3.0).contains(&value.as_number())) && (mean(&uniform) - 2.0).abs() < 0.1);
+        let bernoulli = draws(404, vec![number(0.25), number(5.0), number(-5.0)], 3);
+        assert!(bernoulli.iter().all(|value| [5.0, -5.0].contains(&value.as_number())));
+        assert!((mean(&bernoulli) - -2.5).abs() < 0.5);
+        assert!(draws(404, vec![number(2.0)], 4).iter().all(|value| value.as_number() == 1.0));
+        let choices = draws(405, vec![number(1.0), super::Value::Text(String::from("a"))], 5);
+        assert!(choices.contains(&number(1.0)) && choices.contains(&super::Value::Text(String::from("a"))));
+    }
+
+    #[test]
+    fn text_nodes() {
+        let mut rng = super::Rng::new(1);
+        let text = |text: &str| super::Value::Text(String::from(text));
+        let number = super::Value::Number;
+        assert_eq!(run_value_node(305, vec![text("héllo")], &mut rng), Some(number(5.0)));
+        assert_eq!(run_value_node(306, vec![text("héllo"), number(1.0), number(3.0)], &mut rng), Some(text("éll")));
+        assert_eq!(run_value_node(306, vec![text("hello"), number(-2.0), number(99.0)], &mut rng), Some(text("hello")));
+        assert_eq!(run_value_node(307, vec![text("Mixed")], &mut rng), Some(text("MIXED")));
+        assert_eq!(run_value_node(308, vec![text("Mixed")], &mut rng), Some(text("mixed")));
+        assert_eq!(run_value_node(309, vec![text("banana"), text("nan")], &mut rng), Some(number(1.0)));
+        assert_eq!(run_value_node(309, vec![text("banana"), text("x"), number(5.0), number(-5.0)], &mut rng), Some(number(-5.0)));
+        assert_eq!(run_value_node(310, vec![text("héllo"), text("l")], &mut rng), Some(number(2.0)));
+        assert_eq!(run_value_node(310, vec![text("hello"), text("x")], &mut rng), Some(number(-1.0)));
+        assert_eq!(run_value_node(311, vec![text("a,b,c"), text(","), number(1.0)], &mut rng), Some(text("b")));
+        assert_eq!(run_value_node(311, vec![text("a,b,c"), text(","), number(3.0)], &mut rng), Some(text("")));
+        assert_eq!(run_value_node(312, vec![text("  padded ")], &mut rng), Some(text("padded")));
+        assert_eq!(run_value_node(313, vec![text("abc")], &mut rng), Some(text("cba")));
+        assert_eq!(run_value_node(314, vec![text(" 2.5 ")], &mut rng), Some(number(2.5)));
+        assert_eq!(run_value_node(314, vec![text("two")], &mut rng), Some(number(0.0)));
+        assert_eq!(run_value_node(315, vec![number(2.0)], &mut rng), Some(text("2")));
+        assert_eq!(run_value_node(315, vec![number(2.0 / 3.0), number(2.0)], &mut rng), Some(text("0.67")));
+    }
+
+    #[test]
+    fn regex_nodes() {
+        let mut rng = super::Rng::new(1);
+        let text = |text: &str| super::Value::Text(String::from(text));
+        let number = super::Value::Number;
+        assert_eq!(run_value_node(316, vec![text("^[0-9]+$"), text("1234")], &mut rng), Some(number(1.0)));
+        assert_eq!(run_value_node(316, vec![text("^[0-9]+$"), text("12a"), number(3.0), number(7.0)], &mut rng), Some(number(3.0)));
+        assert_eq!(run_value_node(317, vec![text("a."), text("banana")], &mut rng), Some(number(2.0)));
+        assert_eq!(run_value_node(318, vec![text("id=([0-9]+)"), text("x id=42 y")], &mut rng), Some(text("42")));
+        assert_eq!(run_value_node(318, vec![text("[0-9]+"), text("x 42 y")], &mut rng), Some(text("42")));
+        assert_eq!(run_value_node(318, vec![text("(a)?b"), text("b")], &mut rng), Some(text(""))); //The first group took no part in the match
+        assert_eq!(run_value_node(317, vec![text("(unclosed"), text("(unclosed")], &mut rng), Some(number(0.0)));
+
+        let mut pattern_cache = None;
+        assert_eq!(super::run_regex_node(317, vec![text("b"), text("abba")], &mut pattern_cache), Some(number(2.0)));
+        assert_eq!(pattern_cache.as_ref().unwrap().0, "b");
+        let compiled = pattern_cache.as_ref().unwrap().1.as_ref().unwrap().as_str().as_ptr();
+        assert_eq!(super::run_regex_node(317, vec![text("b"), text("bob")], &mut pattern_cache), Some(number(2.0)));
+        assert_eq!(pattern_cache.as_ref().unwrap().1.as_ref().unwrap().as_str().as_ptr(), compiled); //Not compiled again
+        assert_eq!(super::run_regex_node(317, vec![text("o"), text("bob")], &mut pattern_cache), Some(number(1.0)));
+    }
+
+    #[test]
+    fn network_evaluation() {
+        let mut network = build_structural_test_network();
+        let mut rng = super::Rng::new(0);
+        assert_eq!(network.evaluate(&[2.0, 3.0], &mut rng).unwrap(), vec![5.0, 2.0]); //2 is not greater than 3, so Greater Than returns the lesser input
+        assert_eq!(network.inputs, vec![2.0, 3.0]);
+        assert!(network.evaluate(&[2.0], &mut rng).is_err()); //Input 1 is missing
+    }
+
+}
@@ -0,0 +1,329 @@
+use std::fs;
+use std::ops::Range;
+use crate::core_models::dannn::{Error, Network, Value};
+use crate::core_models::random::Rng;
+
+/*
+A Dataset is a table of rows, each column is either numeric or text. Text columns are passed to the network as
+text values, so they can be used by the string node family (3xx), numeric columns are passed as numbers.
+Some of the columns are targets (the values a network is trained to output), the rest are features, which are fed
+into the network inputs in column order, so the first feature column is input 0, the second is input 1, and so on.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnKind {
+    Numeric,
+    Text,
+}
+
+#[derive(Clone, Debug)]
+pub struct Dataset {
+    column_names: Vec<String>,
+    column_kinds: Vec<ColumnKind>,
+    rows: Vec<Vec<Value>>,
+    feature_columns: Vec<usize>,
+    target_columns: Vec<usize>,
+}
+
+impl Dataset {
+    pub fn from_rows(column_names: Vec<String>, rows: Vec<Vec<Value>>) -> Result<Dataset, Error> {
+        let mut column_kinds = vec![ColumnKind::Numeric; column_names.len()];
+        for row in &rows {
+            if row.len() != column_names.len() { //Every row needs one value per column
+                return Err(Error::DatasetParse);
+            }
+            for (column_index, value) in row.iter().enumerate() {
+                if let Value::Text(_) = value {
+                    column_kinds[column_index] = ColumnKind::Text;
+                }
+            }
+        }
+
+        let mut rows = rows;
+        for row in rows.iter_mut() { //A column with any text in it is a text column, so its numbers become text too
+            for (column_index, value) in row.iter_mut().enumerate() {
+                if column_kinds[column_index] == ColumnKind::Text {
+                    *value = Value::Text(value.as_text());
+                }
+            }
+        }
+
+        let column_count = column_names.len();
+        Ok(Dataset {
+            column_names,
+            column_kinds,
+            rows,
+            feature_columns: (0..column_count.saturating_sub(1)).collect(),
+            target_columns: (column_count.saturating_sub(1)..column_count).collect(), //The last column is the target by default
+        })
+    } //Builds a dataset from an in-memory table
+
+    pub fn from_csv_path(path: &str) -> Result<Dataset, Error> {
+        let text = fs::read_to_string(path).map_err(|_| Error::DatasetRead)?;
+        Dataset::from_csv_str(&text)
+    } //Loads a CSV file, detecting whether the first line is a header
+
+    pub fn from_csv_str(text: &str) -> Result<Dataset, Error> {
+        let records = parse_csv(text)?;
+        let has_header = detect_header(&records);
+        Dataset::from_csv_records(records, has_header)
+    } //Parses CSV text, detecting whether the first line is a header
+
+    pub fn from_csv_str_with_header(text: &str, has_header: bool) -> Result<Dataset, Error> {
+        Dataset::from_csv_records(parse_csv(text)?, has_header)
+    } //Parses CSV text when it is already known whether the first line is a header
+
+    fn from_csv_records(mut records: Vec<Vec<String>>, has_header: bool) -> Result<Dataset, Error> {
+        if records.is_empty() {
+            return Err(Error::DatasetParse);
+        }
+        let column_names = if has_header {
+            records.remove(0)
+        } else {
+            (0..records[0].len()).map(|column_index| format!("column_{}", column_index)).collect()
+        };
+
+        let mut numeric_columns = vec![true; column_names.len()];
+        for record in &records {
+            if record.len() != column_names.len() {
+                return Err(Error::DatasetParse);
+            }
+            for (column_index, field) in record.iter().enumerate() {
+                if !is_numeric(field) {
+                    numeric_columns[column_index] = false;
+                }
+            }
+        }
+
+        let rows = records.into_iter().map(|record| {
+            record.into_iter().enumerate().map(|(column_index, field)| {
+                if numeric_columns[column_index] {
+                    Value::Number(field.trim().parse().unwrap())
+                } else {
+                    Value::Text(field)
+                }
+            }).collect()
+        }).collect();
+        Dataset::from_rows(column_names, rows)
+    }
+
+    pub fn set_targets(&mut self, target_names: &[&str]) -> Result<(), Error> {
+        let mut target_columns = Vec::new();
+        for target_name in target_names {
+            match self.column_index(target_name) {
+                Some(column_index) => target_columns.push(column_index),
+                None => return Err(Error::InvalidColumn),
+            }
+        }
+        self.feature_columns = (0..self.column_names.len()).filter(|column_index| !target_columns.contains(column_index)).collect();
+        self.target_columns = target_columns;
+        Ok(())
+    } //Chooses which columns are targets, every other column becomes a feature
+
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.column_names.iter().position(|column_name| column_name == name)
+    }
+
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    pub fn column_kind(&self, column_index: usize) -> Option<ColumnKind> {
+        self.column_kinds.get(column_index).copied()
+    }
+
+    pub fn feature_names(&self) -> Vec<&str> {
+        self.feature_columns.iter().map(|column_index| self.column_names[*column_index].as_str()).collect()
+    }
+
+    pub fn target_names(&self) -> Vec<&str> {
+        self.target_columns.iter().map(|column_index| self.column_names[*column_index].as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn features(&self, row_index: usize) -> Vec<Value> {
+        self.feature_columns.iter().map(|column_index| self.rows[row_index][*column_index].clone()).collect()
+    } //The values fed into the network inputs for a row
+
+    pub fn targets(&self, row_index: usize) -> Vec<Value> {
+        self.target_columns.iter().map(|column_index| self.rows[row_index][*column_index].clone()).collect()
+    } //The values the network should output for a row
+
+    pub fn split(&self, validation_fraction: f64, test_fraction: f64, seed: u64) -> Result<(Dataset, Dataset, Dataset), Error> {
+        if validation_fraction < 0.0 || test_fraction < 0.0 || validation_fraction + test_fraction > 1.0 {
+            return Err(Error::InvalidSplit);
+        }
+        let mut row_indices: Vec<usize> = (0..self.rows.len()).collect();
+        Rng::new(seed).shuffle(&mut row_indices);
+
+        let validation_count = (self.rows.len() as f64 * validation_fraction).round() as usize;
+        let test_count = ((self.rows.len() as f64 * test_fraction).round() as usize).min(self.rows.len() - validation_count);
+        let train_count = self.rows.len() - validation_count - test_count;
+
+        let train = self.with_rows(&row_indices[..train_count]);
+        let validation = self.with_rows(&row_indices[train_count..train_count + validation_count]);
+        let test = self.with_rows(&row_indices[train_count + validation_count..]);
+        Ok((train, validation, test))
+    } //Shuffles the rows with a seed and splits them into train, validation, and test datasets
+
+    fn with_rows(&self, row_indices: &[usize]) -> Dataset {
+        Dataset {
+            column_names: self.column_names.clone(),
+            column_kinds: self.column_kinds.clone(),
+            rows: row_indices.iter().map(|row_index| self.rows[*row_index].clone()).collect(),
+            feature_columns: self.feature_columns.clone(),
+            target_columns: self.target_columns.clone(),
+        }
+    }
+
+    pub fn batches(&self, batch_size: usize) -> Vec<Range<usize>> {
+        let batch_size = batch_size.max(1);
+        (0..self.rows.len()).step_by(batch_size).map(|start| start..(start + batch_size).min(self.rows.len())).collect()
+    } //Row ranges of at most batch_size rows, covering the whole dataset
+
+    pub fn evaluate_batch(&self, network: &mut Network, rows: Range<usize>, rng: &mut Rng) -> Result<Vec<Vec<Value>>, Error> {
+        if rows.end > self.rows.len() {
+            return Err(Error::InvalidSplit);
+        }
+        network.reset_state(); //Each call is one episode for memory nodes
+        let mut outputs = Vec::new();
+        for row_index in rows {
+            outputs.push(network.evaluate_values(&self.features(row_index), rng)?);
+        }
+        Ok(outputs)
+    } //Feeds a range of rows through the network in order, returns the outputs for each row
+
+    pub fn evaluate(&self, network: &mut Network, rng: &mut Rng) -> Result<Vec<Vec<Value>>, Error> {
+        self.evaluate_batch(network, 0..self.rows.len(), rng)
+    } //Feeds every row through the network
+}
+
+fn is_numeric(field: &str) -> bool {
+    field.trim().parse::<f64>().is_ok()
+}
+
+fn detect_header(records: &[Vec<String>]) -> bool {
+    /*
+    The first line is treated as a header when it has text in a column that is numeric everywhere else. When every
+    column is text this can't be told apart, so the first line is a header if none of its fields repeat in the data.
+     */
+    if records.len() < 2 {
+        return false;
+    }
+    let first = &records[0];
+    for (column_index, field) in first.iter().enumerate() {
+        let rest_numeric = records[1..].iter().all(|record| record.get(column_index).is_some_and(|value| is_numeric(value)));
+        if rest_numeric && !is_numeric(field) {
+            return true;
+        }
+    }
+    if first.iter().all(|field| !is_numeric(field)) {
+        return first.iter().enumerate().all(|(column_index, field)| {
+            records[1..].iter().all(|record| record.get(column_index) != Some(field))
+        });
+    }
+    false
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut records = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(parse_csv_line(line)?);
+    }
+    Ok(records)
+}
+
+fn parse_csv_line(line: &str) -> Result<Vec<String>, Error> {
+    //Splits a line on commas, fields can be quoted to hold commas, and quotes inside of quoted fields are doubled
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut characters = line.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            '"' if quoted => {
+                if characters.peek() == Some(&'"') {
+                    field.push('"');
+                    characters.next();
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(character),
+        }
+    }
+    if quoted { //Unterminated quote
+        return Err(Error::DatasetParse);
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::{ColumnKind, Dataset};
+    use crate::core_models::dannn::{Network, Value};
+    use crate::core_models::random::Rng;
+
+    #[test]
+    fn csv_header_and_column_detection() {
+        let dataset = Dataset::from_csv_str("x,name,y\n1.5,\"a, b\",3\n2,c,4\n").unwrap();
+        assert_eq!(dataset.column_names(), &["x", "name", "y"]);
+        assert_eq!(dataset.column_kind(0), Some(ColumnKind::Numeric));
+        assert_eq!(dataset.column_kind(1), Some(ColumnKind::Text));
+        assert_eq!(dataset.features(0), vec![Value::Number(1.5), Value::Text(String::from("a, b"))]);
+        assert_eq!(dataset.targets(1), vec![Value::Number(4.0)]);
+
+        let headerless = Dataset::from_csv_str("1,2\n3,4\n").unwrap();
+        assert_eq!(headerless.len(), 2);
+        assert_eq!(headerless.column_names(), &["column_0", "column_1"]);
+        assert!(Dataset::from_csv_str("a,b\n1\n").is_err());
+    }
+
+    #[test]
+    fn seeded_split_is_reproducible() {
+        let rows = (0..20).map(|index| vec![Value::Number(index as f64), Value::Number(0.0)]).collect();
+        let mut dataset = Dataset::from_rows(vec![String::from("a"), String::from("b")], rows).unwrap();
+        dataset.set_targets(&["a"]).unwrap();
+        assert_eq!(dataset.feature_names(), vec!["b"]);
+
+        let (train, validation, test) = dataset.split(0.25, 0.25, 7).unwrap();
+        let (train_again, _, _) = dataset.split(0.25, 0.25, 7).unwrap();
+        assert_eq!((train.len(), validation.len(), test.len()), (10, 5, 5));
+        assert_eq!(train.targets(0), train_again.targets(0));
+        assert!(dataset.split(0.75, 0.5, 7).is_err());
+    }
+
+    #[test]
+    fn batches_feed_network_evaluation() {
+        let dataset = Dataset::from_csv_str("a,b,sum\n1,2,3\n4,5,9\n6,7,13\n").unwrap();
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(201, 0);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_input(0, 0, 1).unwrap();
+
+        let batches = dataset.batches(2);
+        assert_eq!(batches, vec![0..2, 2..3]);
+        let mut rng = Rng::new(0);
+        let outputs = dataset.evaluate_batch(&mut network, batches[1].clone(), &mut rng).unwrap();
+        assert_eq!(outputs, vec![vec![Value::Number(13.0)]]);
+        for (row_index, output) in dataset.evaluate(&mut network, &mut rng).unwrap().iter().enumerate() {
+            assert_eq!(*output, dataset.targets(row_index));
+        }
+    }
+}
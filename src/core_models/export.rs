@@ -0,0 +1,200 @@
+use crate::core_models::dannn::{is_memory_node, weighted_terms, ConnectionType, Network, NodeSelectionTable};
+
+/*
+Exports a network into formats meant for people and other programs rather than for loading back (see the
+serialization module for that):
+ - DOT, for drawing the network with Graphviz
+ - expressions, one formula per output, written out as a tree (a node read by several others is repeated)
+ - Rust, a function that evaluates the network node by node through run_value_node, so it behaves exactly like the
+   network for every node type. Networks with memory nodes get an extra memory slice with one number per memory node,
+   zeroed by the caller at the start of each episode
+
+Inputs are named by the given input names, or x0, x1, ... when there are fewer names than inputs.
+ */
+
+fn input_name(input_names: &[String], input_id: usize) -> String {
+    match input_names.get(input_id) {
+        Some(name) => name.clone(),
+        None => format!("x{}", input_id),
+    }
+}
+
+fn node_name(node_type: usize) -> String {
+    NodeSelectionTable::node_type_name(node_type).unwrap_or(format!("Node {}", node_type))
+}
+
+fn function_name(node_type: usize) -> String {
+    node_name(node_type).to_lowercase().replace(' ', "_")
+} //"Greater Than Gate" becomes greater_than_gate
+
+pub fn to_dot(network: &Network, input_names: &[String]) -> String {
+    let mut text = String::from("digraph network {\n    rankdir=LR;\n");
+    let mut used_inputs = Vec::new();
+    let mut edges = String::new();
+    let mut constant_count = 0;
+    let last_layer = network.layers.len().saturating_sub(1);
+
+    for (layer_index, layer) in network.layers.iter().enumerate() {
+        for (node_index, node) in layer.nodes.iter().enumerate() {
+            let shape = if layer_index == last_layer { "doublecircle" } else { "circle" };
+            text.push_str(&format!("    n{}_{} [label=\"{}\\n{}\", shape={}];\n", layer_index, node_index, escape(&node_name(node.node_type_id)), node.node_type_id, shape));
+            for connection in &node.connections {
+                let source = match connection {
+                    ConnectionType::Connection(node_connection) => format!("n{}_{}", node_connection.source_layer, node_connection.source_node),
+                    ConnectionType::Input(input) => {
+                        if !used_inputs.contains(&input.input_id) {
+                            used_inputs.push(input.input_id);
+                        }
+                        format!("input{}", input.input_id)
+                    }
+                    ConnectionType::Constant(constant) => {
+                        text.push_str(&format!("    constant{} [label=\"{}\", shape=plaintext];\n", constant_count, constant.value));
+                        constant_count += 1;
+                        format!("constant{}", constant_count - 1)
+                    }
+                };
+                edges.push_str(&format!("    {} -> n{}_{};\n", source, layer_index, node_index));
+            }
+        }
+    }
+    used_inputs.sort_unstable();
+    for input_id in used_inputs {
+        text.push_str(&format!("    input{} [label=\"{}\", shape=box];\n", input_id, escape(&input_name(input_names, input_id))));
+    }
+    text.push_str(&edges);
+    text.push_str("}\n");
+    text
+} //Inputs are boxes, constants are plain text and output nodes are double circles
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn expression_of(network: &Network, layer_index: usize, node_index: usize, input_names: &[String]) -> String {
+    let node = &network.layers[layer_index].nodes[node_index];
+    let arguments: Vec<String> = node.connections.iter().map(|connection| match connection {
+        ConnectionType::Connection(node_connection) => expression_of(network, node_connection.source_layer, node_connection.source_node, input_names),
+        ConnectionType::Input(input) => input_name(input_names, input.input_id),
+        ConnectionType::Constant(constant) => constant.value.to_string(),
+    }).collect();
+
+    let infix = |operator: &str| format!("({})", arguments.join(&format!(" {} ", operator)));
+    if node.node_type_id == 608 { //Weighted Sum
+        let terms: Vec<String> = weighted_terms(&node.connections).iter().map(|(value, weight)| match weight {
+            Some(weight) => format!("{} * {}", arguments[*value], arguments[*weight]),
+            None => arguments[*value].clone(),
+        }).collect();
+        return format!("({})", terms.join(" + "));
+    }
+    match (node.node_type_id, arguments.len()) {
+        (113, 1) => arguments[0].clone(), //Passthrough
+        (201, count) if count > 1 => infix("+"),
+        (202, count) if count > 1 => infix("-"),
+        (203, count) if count > 1 => infix("*"),
+        (204, count) if count > 1 => infix("/"),
+        (217, 1) => format!("-{}", arguments[0]),
+        (node_type, _) => format!("{}({})", function_name(node_type), arguments.join(", ")),
+    }
+}
+
+pub fn to_expressions(network: &Network, input_names: &[String]) -> Vec<String> {
+    match network.layers.last() {
+        Some(layer) => (0..layer.nodes.len()).map(|node_index| expression_of(network, network.layers.len() - 1, node_index, input_names)).collect(),
+        None => Vec::new(),
+    }
+} //One expression per output. Add, subtract, multiply and divide are written infix, other nodes as function calls
+
+pub fn to_rust(network: &Network, function_name: &str) -> String {
+    let mut text = if network.has_memory() {
+        let mut text = String::from("use steel_ml::core_models::dannn::{run_memory_node, run_value_node, Value};\nuse steel_ml::core_models::random::Rng;\n\n");
+        text.push_str(&format!("pub fn {}(inputs: &[Value], memory: &mut [f64], rng: &mut Rng) -> Option<Vec<Value>> {{\n", function_name));
+        text
+    } else {
+        let mut text = String::from("use steel_ml::core_models::dannn::{run_value_node, Value};\nuse steel_ml::core_models::random::Rng;\n\n");
+        text.push_str(&format!("pub fn {}(inputs: &[Value], rng: &mut Rng) -> Option<Vec<Value>> {{\n", function_name));
+        text
+    };
+    let mut memory_count = 0;
+    for (layer_index, layer) in network.layers.iter().enumerate() {
+        for (node_index, node) in layer.nodes.iter().enumerate() {
+            let mut arguments: Vec<String> = node.connections.iter().map(|connection| match connection {
+                ConnectionType::Connection(node_connection) => format!("n{}_{}.clone()", node_connection.source_layer, node_connection.source_node),
+                ConnectionType::Input(input) => format!("inputs.get({})?.clone()", input.input_id),
+                ConnectionType::Constant(constant) => format!("Value::Number({:?})", constant.value),
+            }).collect();
+            if node.node_type_id == 608 { //Weighted Sum, the network weights its inputs before running the node
+                arguments = weighted_terms(&node.connections).iter().map(|(value, weight)| match weight.map(|weight| &node.connections[weight]) {
+                    Some(ConnectionType::Constant(weight)) => format!("Value::Number({}.as_number() * {:?})", arguments[*value], weight.value),
+                    _ => arguments[*value].clone(),
+                }).collect();
+            }
+            if is_memory_node(node.node_type_id) {
+                let numbers: Vec<String> = arguments.iter().map(|argument| format!("{}.as_number()", argument)).collect();
+                text.push_str(&format!("    let n{}_{} = Value::Number(run_memory_node({}, vec![{}], memory.get_mut({})?)?); //{}\n", layer_index, node_index, node.node_type_id, numbers.join(", "), memory_count, node_name(node.node_type_id)));
+                memory_count += 1;
+            } else {
+                text.push_str(&format!("    let n{}_{} = run_value_node({}, vec![{}], rng)?; //{}\n", layer_index, node_index, node.node_type_id, arguments.join(", "), node_name(node.node_type_id)));
+            }
+        }
+    }
+    let outputs: Vec<String> = match network.layers.last() {
+        Some(layer) => (0..layer.nodes.len()).map(|node_index| format!("n{}_{}", network.layers.len() - 1, node_index)).collect(),
+        None => Vec::new(),
+    };
+    text.push_str(&format!("    Some(vec![{}])\n}}\n", outputs.join(", ")));
+    text
+} //A function returning the same outputs as Network::evaluate_values, None where evaluating would fail
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network() -> Network {
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(201, 0);
+        let _ = network.generate_node_to_layer(207, 0);
+        let _ = network.generate_node_to_layer(203, 1);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_constant(0, 0, 1.5).unwrap();
+        network.connect_input(0, 1, 1).unwrap();
+        network.connect_node(1, 0, 0, 0).unwrap();
+        network.connect_node(1, 0, 0, 1).unwrap();
+        network
+    }
+
+    #[test]
+    fn expressions_and_dot() {
+        let names = vec![String::from("a")];
+        assert_eq!(to_expressions(&network(), &names), vec!["((a + 1.5) * sin(x1))"]);
+        let dot = to_dot(&network(), &names);
+        assert!(dot.starts_with("digraph network {"));
+        assert!(dot.contains("    n1_0 [label=\"Multiply\\n203\", shape=doublecircle];\n"));
+        assert!(dot.contains("    input0 [label=\"a\", shape=box];\n"));
+        assert!(dot.contains("    constant0 -> n0_0;\n    input1 -> n0_1;\n    n0_0 -> n1_0;\n"));
+
+        let mut network = network();
+        network.replace_node_type(0, 0, 608).unwrap();
+        network.connect_constant(0, 0, 2.0).unwrap();
+        assert_eq!(to_expressions(&network, &names), vec!["((a * 1.5 + 2) * sin(x1))"]);
+        assert!(to_rust(&network, "predict").contains("run_value_node(608, vec![Value::Number(inputs.get(0)?.clone().as_number() * 1.5), Value::Number(2.0)], rng)?;"));
+    }
+
+    #[test]
+    fn rust_export() {
+        let rust = to_rust(&network(), "predict");
+        assert!(rust.contains("pub fn predict(inputs: &[Value], rng: &mut Rng) -> Option<Vec<Value>> {\n"));
+        assert!(rust.contains("    let n0_0 = run_value_node(201, vec![inputs.get(0)?.clone(), Value::Number(1.5)], rng)?; //Add\n"));
+        assert!(rust.contains("    let n1_0 = run_value_node(203, vec![n0_0.clone(), n0_1.clone()], rng)?; //Multiply\n"));
+        assert!(rust.ends_with("    Some(vec![n1_0])\n}\n"));
+
+        let mut network = network();
+        network.replace_node_type(0, 1, 701).unwrap();
+        let rust = to_rust(&network, "predict");
+        assert!(rust.contains("pub fn predict(inputs: &[Value], memory: &mut [f64], rng: &mut Rng) -> Option<Vec<Value>> {\n"));
+        assert!(rust.contains("    let n0_1 = Value::Number(run_memory_node(701, vec![inputs.get(1)?.clone().as_number()], memory.get_mut(0)?)?); //Delay\n"));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::core_models::dannn::{Error, Network, Value};
+use crate::core_models::dataset::Dataset;
+use crate::core_models::random::Rng;
+
+/*
+Fitness in this crate is always a loss: lower is better, and a perfect network scores 0. Metrics where higher is
+better (accuracy, F1) are turned into a loss by subtracting them from 1. The parsimony penalty adds a cost per node
+to the loss, so that between two networks that are equally accurate, the smaller one is preferred.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Metric {
+    MeanSquaredError,
+    MeanAbsoluteError,
+    RootMeanSquaredError,
+    LogLoss,
+    Accuracy,
+    F1Score,
+    Huber(f64), //Delta, the error size where the loss switches from squared to linear
+    EditDistance, //Mean edit distance between text outputs and text targets, for networks using string nodes
+}
+
+#[derive(Clone, Debug)]
+pub struct Fitness {
+    metric: Metric,
+    parsimony_coefficient: f64, //Loss added per node in the network
+}
+
+impl Fitness {
+    pub fn new(metric: Metric) -> Fitness {
+        Fitness {
+            metric,
+            parsimony_coefficient: 0.0,
+        }
+    }
+
+    pub fn with_parsimony(mut self, parsimony_coefficient: f64) -> Fitness {
+        self.parsimony_coefficient = parsimony_coefficient;
+        self
+    } //Adds a penalty of parsimony_coefficient per node to the loss
+
+    pub fn metric(&self) -> &Metric {
+        &self.metric
+    }
+
+    pub fn loss(&self, outputs: &[Vec<Value>], targets: &[Vec<Value>]) -> f64 {
+        let (outputs, targets) = pair_values(outputs, targets);
+        let numbers = |values: &[Value]| -> Vec<f64> { values.iter().map(Value::as_number).collect() };
+        let texts = |values: &[Value]| -> Vec<String> { values.iter().map(Value::as_text).collect() };
+        match self.metric {
+            Metric::MeanSquaredError => mean_squared_error(&numbers(&outputs), &numbers(&targets)),
+            Metric::MeanAbsoluteError => mean_absolute_error(&numbers(&outputs), &numbers(&targets)),
+            Metric::RootMeanSquaredError => root_mean_squared_error(&numbers(&outputs), &numbers(&targets)),
+            Metric::LogLoss => log_loss(&numbers(&outputs), &numbers(&targets)),
+            Metric::Accuracy => 1.0 - accuracy(&numbers(&outputs), &numbers(&targets)),
+            Metric::F1Score => 1.0 - f1_score(&numbers(&outputs), &numbers(&targets)),
+            Metric::Huber(delta) => huber_loss(&numbers(&outputs), &numbers(&targets), delta),
+            Metric::EditDistance => mean_edit_distance(&texts(&outputs), &texts(&targets)),
+        }
+    } //Loss of a set of output rows against target rows, without the parsimony penalty
+
+    pub fn parsimony_penalty(&self, network: &Network) -> f64 {
+        self.parsimony_coefficient * network.node_count() as f64
+    }
+
+    pub fn evaluate(&self, network: &mut Network, dataset: &Dataset, rng: &mut Rng) -> Result<f64, Error> {
+        let outputs = dataset.evaluate(network, rng)?;
+        let targets: Vec<Vec<Value>> = (0..dataset.len()).map(|row_index| dataset.targets(row_index)).collect();
+        Ok(self.loss(&outputs, &targets) + self.parsimony_penalty(network))
+    } //Runs the network over a dataset and returns the loss including the parsimony penalty
+}
+
+fn pair_values(outputs: &[Vec<Value>], targets: &[Vec<Value>]) -> (Vec<Value>, Vec<Value>) {
+    //Flattens rows into matching output and target lists, a network with too few outputs is scored as if it output 0
+    let mut paired_outputs = Vec::new();
+    let mut paired_targets = Vec::new();
+    for (output_row, target_row) in outputs.iter().zip(targets) {
+        for (target_index, target) in target_row.iter().enumerate() {
+            paired_outputs.push(output_row.get(target_index).cloned().unwrap_or(Value::Number(0.0)));
+            paired_targets.push(target.clone());
+        }
+    }
+    (paired_outputs, paired_targets)
+}
+
+pub fn mean_squared_error(outputs: &[f64], targets: &[f64]) -> f64 {
+    mean(outputs.iter().zip(targets).map(|(output, target)| (output - target).powi(2)))
+}
+
+pub fn mean_absolute_error(outputs: &[f64], targets: &[f64]) -> f64 {
+    mean(outputs.iter().zip(targets).map(|(output, target)| (output - target).abs()))
+}
+
+pub fn root_mean_squared_error(outputs: &[f64], targets: &[f64]) -> f64 {
+    mean_squared_error(outputs, targets).sqrt()
+}
+
+pub fn log_loss(outputs: &[f64], targets: &[f64]) -> f64 {
+    //Outputs are probabilities of the positive class, targets above 0.5 are positive
+    let epsilon = 1e-15;
+    mean(outputs.iter().zip(targets).map(|(output, target)| {
+        let probability = output.clamp(epsilon, 1.0 - epsilon);
+        if *target > 0.5 {
+            -probability.ln()
+        } else {
+            -(1.0 - probability).ln()
+        }
+    }))
+}
+
+pub fn accuracy(outputs: &[f64], targets: &[f64]) -> f64 {
+    //Outputs and targets are rounded to the nearest class
+    mean(outputs.iter().zip(targets).map(|(output, target)| {
+        if output.round() == target.round() { 1.0 } else { 0.0 }
+    }))
+}
+
+pub fn f1_score(outputs: &[f64], targets: &[f64]) -> f64 {
+    //Binary F1, values above 0.5 are the positive class
+    let mut true_positives = 0.0;
+    let mut false_positives = 0.0;
+    let mut false_negatives = 0.0;
+    for (output, target) in outputs.iter().zip(targets) {
+        match (*output > 0.5, *target > 0.5) {
+            (true, true) => true_positives += 1.0,
+            (true, false) => false_positives += 1.0,
+            (false, true) => false_negatives += 1.0,
+            (false, false) => {}
+        }
+    }
+    if true_positives == 0.0 {
+        return 0.0;
+    }
+    2.0 * true_positives / (2.0 * true_positives + false_positives + false_negatives)
+}
+
+pub fn huber_loss(outputs: &[f64], targets: &[f64], delta: f64) -> f64 {
+    mean(outputs.iter().zip(targets).map(|(output, target)| {
+        let error = (output - target).abs();
+        if error <= delta {
+            0.5 * error * error
+        } else {
+            delta * (error - 0.5 * delta)
+        }
+    }))
+}
+
+pub fn edit_distance(first: &str, second: &str) -> usize {
+    //Levenshtein distance, the amount of single character insertions, deletions, and substitutions between two strings
+    let second: Vec<char> = second.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=second.len()).collect();
+    for (first_index, first_character) in first.chars().enumerate() {
+        let mut current_row = vec![first_index + 1];
+        for (second_index, second_character) in second.iter().enumerate() {
+            let substitution = previous_row[second_index] + if first_character == *second_character { 0 } else { 1 };
+            let insertion = current_row[second_index] + 1;
+            let deletion = previous_row[second_index + 1] + 1;
+            current_row.push(substitution.min(insertion).min(deletion));
+        }
+        previous_row = current_row;
+    }
+    previous_row[second.len()]
+}
+
+pub fn mean_edit_distance(outputs: &[String], targets: &[String]) -> f64 {
+    mean(outputs.iter().zip(targets).map(|(output, target)| edit_distance(output, target) as f64))
+}
+
+fn mean<I>(values: I) -> f64 where I: Iterator<Item = f64> {
+    let mut total = 0.0;
+    let mut count = 0;
+    for value in values {
+        total += value;
+        count += 1;
+    }
+    if count == 0 {
+        return 0.0;
+    }
+    total / count as f64
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regression_metrics() {
+        let outputs = [1.0, 2.0, 5.0];
+        let targets = [1.0, 4.0, 2.0];
+        assert_eq!(mean_squared_error(&outputs, &targets), 13.0 / 3.0);
+        assert_eq!(mean_absolute_error(&outputs, &targets), 5.0 / 3.0);
+        assert_eq!(root_mean_squared_error(&outputs, &targets), (13.0f64 / 3.0).sqrt());
+        assert_eq!(huber_loss(&outputs, &targets, 1.0), (0.0 + 1.5 + 2.5) / 3.0);
+        assert_eq!(mean_squared_error(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn classification_metrics() {
+        let outputs = [0.9, 0.2, 0.7, 0.1];
+        let targets = [1.0, 0.0, 0.0, 1.0];
+        assert_eq!(accuracy(&outputs, &targets), 0.5);
+        assert_eq!(f1_score(&outputs, &targets), 0.5);
+        assert!(log_loss(&[1.0, 0.0], &[1.0, 0.0]) < 1e-10);
+        assert!(log_loss(&[0.0], &[1.0]).is_finite());
+    }
+
+    #[test]
+    fn edit_distance_metric() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+        let fitness = Fitness::new(Metric::EditDistance);
+        let outputs = vec![vec![Value::Text(String::from("abc"))], vec![Value::Number(12.0)]];
+        let targets = vec![vec![Value::Text(String::from("abd"))], vec![Value::Text(String::from("12"))]];
+        assert_eq!(fitness.loss(&outputs, &targets), 0.5);
+    }
+
+    #[test]
+    fn parsimony_penalty_favors_smaller_networks() {
+        let dataset = Dataset::from_csv_str("a,b\n1,1\n2,2\n").unwrap();
+        let mut small = Network::new();
+        let _ = small.generate_layer_to_network();
+        let _ = small.generate_node_to_layer(113, 0);
+        small.connect_input(0, 0, 0).unwrap();
+
+        let mut large = Network::new();
+        let _ = large.generate_layer_to_network();
+        let _ = large.generate_layer_to_network();
+        let _ = large.generate_node_to_layer(113, 0);
+        let _ = large.generate_node_to_layer(113, 1);
+        large.connect_input(0, 0, 0).unwrap();
+        large.connect_node(1, 0, 0, 0).unwrap();
+
+        let mut rng = Rng::new(0);
+        let fitness = Fitness::new(Metric::MeanSquaredError).with_parsimony(0.01);
+        let small_loss = fitness.evaluate(&mut small, &dataset, &mut rng).unwrap();
+        let large_loss = fitness.evaluate(&mut large, &dataset, &mut rng).unwrap();
+        assert_eq!(small_loss, 0.01);
+        assert!(small_loss < large_loss);
+        assert_eq!(Fitness::new(Metric::Accuracy).loss(&[vec![Value::Number(1.0)]], &[vec![Value::Number(1.0)]]), 0.0);
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::*;
+use crate::core_models::dannn::{Error, Network};
+use crate::core_models::serialization::{self, parse};
+
+/*
+MAP-Elites keeps the best network found for every combination of behaviors instead of only the best network overall.
+Each behavior dimension is a user chosen measurement of a network (for example its complexity, or how many of its
+nodes are logic nodes) split into a number of bins over a range. Every combination of bins is a cell, and a cell only
+keeps a new network if it has a lower loss than the network already there.
+
+Archives are saved as text (see the serialization module for the network part), so a search can be resumed later:
+
+    dimension 0 50 10 node count
+    elite 3 4
+    loss 0.5
+    behavior 31 0.4
+    layer
+    node 201
+    ...
+    end
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BehaviorDimension {
+    pub name: String,
+    pub minimum: f64,
+    pub maximum: f64, //Values outside of the range fall in the first or last bin
+    pub bins: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Elite {
+    pub network: Network,
+    pub loss: f64,
+    pub behavior: Vec<f64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Archive {
+    dimensions: Vec<BehaviorDimension>,
+    cells: BTreeMap<Vec<usize>, Elite>,
+}
+
+impl Archive {
+    pub fn new(dimensions: Vec<BehaviorDimension>) -> Archive {
+        Archive {
+            dimensions,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn dimensions(&self) -> &[BehaviorDimension] {
+        &self.dimensions
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn coverage(&self) -> f64 {
+        let cell_count: usize = self.dimensions.iter().map(|dimension| dimension.bins.max(1)).product();
+        self.cells.len() as f64 / cell_count as f64
+    } //Fraction of cells holding an elite
+
+    pub fn cell_of(&self, behavior: &[f64]) -> Option<Vec<usize>> {
+        if behavior.len() != self.dimensions.len() {
+            return None;
+        }
+        let mut cell = Vec::new();
+        for (value, dimension) in behavior.iter().zip(&self.dimensions) {
+            if value.is_nan() {
+                return None;
+            }
+            let bins = dimension.bins.max(1);
+            let fraction = (value - dimension.minimum) / (dimension.maximum - dimension.minimum);
+            let bin = (fraction * bins as f64).floor();
+            cell.push(if bin.is_nan() { 0 } else { bin.clamp(0.0, (bins - 1) as f64) as usize });
+        }
+        Some(cell)
+    } //Cell a behavior falls into, None if the behavior has the wrong amount of dimensions or is NaN
+
+    pub fn insert(&mut self, network: Network, loss: f64, behavior: Vec<f64>) -> bool {
+        let cell = match self.cell_of(&behavior) {
+            Some(cell) => cell,
+            None => return false,
+        };
+        if let Some(elite) = self.cells.get(&cell) {
+            if elite.loss <= loss {
+                return false;
+            }
+        }
+        self.cells.insert(cell, Elite { network, loss, behavior });
+        true
+    } //Stores the network if its cell is empty or it beats the elite in its cell, returns whether it was stored
+
+    pub fn get(&self, cell: &[usize]) -> Option<&Elite> {
+        self.cells.get(cell)
+    }
+
+    pub fn elites(&self) -> Vec<(&Vec<usize>, &Elite)> {
+        self.cells.iter().collect()
+    } //Every stored elite and its cell, in cell order
+
+    pub fn best(&self) -> Option<&Elite> {
+        self.cells.values().min_by(|first, second| first.loss.total_cmp(&second.loss))
+    } //Elite with the lowest loss over all cells
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for dimension in &self.dimensions {
+            text.push_str(&format!("dimension {} {} {} {}\n", dimension.minimum, dimension.maximum, dimension.bins, dimension.name));
+        }
+        for (cell, elite) in &self.cells {
+            text.push_str(&format!("elite {}\n", join(cell)));
+            text.push_str(&format!("loss {}\n", elite.loss));
+            text.push_str(&format!("behavior {}\n", join(&elite.behavior)));
+            text.push_str(&serialization::network_to_text(&elite.network));
+            text.push_str("end\n");
+        }
+        text
+    } //Saves the whole archive, including networks, so that it can be resumed
+
+    pub fn from_text(text: &str) -> Result<Archive, Error> {
+        let mut archive = Archive::new(Vec::new());
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["dimension", minimum, maximum, bins, name @ ..] => archive.dimensions.push(BehaviorDimension {
+                    name: name.join(" "),
+                    minimum: parse(minimum)?,
+                    maximum: parse(maximum)?,
+                    bins: parse(bins)?,
+                }),
+                ["elite", cell @ ..] => {
+                    let cell = cell.iter().map(|bin| parse(bin)).collect::<Result<Vec<usize>, Error>>()?;
+                    let loss = match lines.next().map(|line| line.split_whitespace().collect::<Vec<&str>>()).as_deref() {
+                        Some(["loss", loss]) => parse(loss)?,
+                        _ => return Err(Error::ModelParse),
+                    };
+                    let behavior = match lines.next().and_then(|line| line.strip_prefix("behavior")) {
+                        Some(values) => values.split_whitespace().map(parse).collect::<Result<Vec<f64>, Error>>()?,
+                        None => return Err(Error::ModelParse),
+                    };
+                    let network = serialization::read_network_block(&mut lines)?;
+                    if archive.cell_of(&behavior) != Some(cell.clone()) { //Saved with different dimensions
+                        return Err(Error::ModelParse);
+                    }
+                    archive.cells.insert(cell, Elite { network, loss, behavior });
+                }
+                _ => return Err(Error::ModelParse),
+            }
+        }
+        Ok(archive)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        serialization::write_atomically(path, &self.to_text())
+    }
+
+    pub fn load(path: &str) -> Result<Archive, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::FileRead)?;
+        Archive::from_text(&text)
+    }
+
+    pub fn summary_csv(&self) -> String {
+        let mut text = String::from("cell,");
+        for dimension in &self.dimensions {
+            text.push_str(&format!("\"{}\",", dimension.name.replace('"', "\"\"")));
+        }
+        text.push_str("loss,node_count\n");
+        for (cell, elite) in &self.cells {
+            text.push_str(&format!("{},{},{},{}\n", join(cell), elite.behavior.iter().map(f64::to_string).collect::<Vec<String>>().join(","), elite.loss, elite.network.node_count()));
+        }
+        text
+    } //Exports one row per elite (cell, behavior values, loss and size) without the networks, for plotting
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(ToString::to_string).collect::<Vec<String>>().join(" ")
+}
+
+pub fn family_fraction(network: &Network, family: usize) -> f64 {
+    let complexity = network.complexity();
+    if complexity.node_count == 0 {
+        return 0.0;
+    }
+    *complexity.family_histogram.get(&family).unwrap_or(&0) as f64 / complexity.node_count as f64
+} //Fraction of the nodes of a network that belong to a node family (100 for logic, 200 for arithmetic, ...), a ready made behavior
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dimensions() -> Vec<BehaviorDimension> {
+        vec![
+            BehaviorDimension { name: String::from("node count"), minimum: 0.0, maximum: 10.0, bins: 5 },
+            BehaviorDimension { name: String::from("logic fraction"), minimum: 0.0, maximum: 1.0, bins: 4 },
+        ]
+    }
+
+    fn network(node_type: usize) -> Network {
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(node_type, 0);
+        network.connect_input(0, 0, 0).unwrap();
+        network
+    }
+
+    #[test]
+    fn cells_keep_the_best_network() {
+        let mut archive = Archive::new(dimensions());
+        assert_eq!(archive.cell_of(&[3.0, 1.0]), Some(vec![1, 3]));
+        assert_eq!(archive.cell_of(&[-5.0, 0.3]), Some(vec![0, 1]));
+        assert_eq!(archive.cell_of(&[3.0]), None);
+
+        assert!(archive.insert(network(201), 2.0, vec![1.0, 0.0]));
+        assert!(!archive.insert(network(202), 3.0, vec![1.5, 0.1]));
+        assert!(archive.insert(network(203), 1.0, vec![1.5, 0.1]));
+        assert!(archive.insert(network(113), 5.0, vec![1.0, 1.0]));
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.coverage(), 0.1);
+        assert_eq!(archive.best().unwrap().network.layers[0].nodes[0].node_type_id, 203);
+        assert_eq!(family_fraction(&network(113), 100), 1.0);
+    }
+
+    #[test]
+    fn archive_text_round_trip() {
+        let mut archive = Archive::new(dimensions());
+        archive.insert(network(201), 0.1 + 0.2, vec![1.0, 0.0]);
+        archive.insert(network(113), 5.0, vec![9.0, 1.0]);
+
+        let loaded = Archive::from_text(&archive.to_text()).unwrap();
+        assert_eq!(loaded.dimensions(), archive.dimensions());
+        assert_eq!(loaded.to_text(), archive.to_text());
+        assert_eq!(loaded.get(&[0, 0]).unwrap().loss, 0.1 + 0.2);
+        assert_eq!(archive.summary_csv(), "cell,\"node count\",\"logic fraction\",loss,node_count\n0 0,1,0,0.30000000000000004,1\n4 3,9,1,5,1\n");
+        assert!(Archive::from_text("elite 0 0\nloss 1\n").is_err());
+    }
+}
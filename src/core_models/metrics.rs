@@ -0,0 +1,210 @@
+use std::collections::*;
+use std::io::Write;
+use crate::core_models::dannn::{Error, Network};
+use crate::core_models::speciation::{self, SpeciationSettings};
+use crate::core_models::trainer::{Trainer, TrainerHooks};
+
+/*
+Statistics of a trainers population, measured once per generation after it has been evaluated. A MetricsLog writes
+them as JSON lines (one JSON object per generation, one per line) so a run can be plotted while it is still going:
+
+    {"generation":3,"best_loss":0.5,"mean_loss":12.25,"median_loss":4,"best_so_far":0.5,"diversity":0.61,...}
+
+Losses that are not finite (networks that failed to evaluate) are left out of the mean and median, and a value that
+can't be computed is written as null. Diversity is the mean structural distance between every pair of networks in the
+population (with the default speciation coefficients), so 0 means every network has the same structure and constants.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationMetrics {
+    pub generation: usize,
+    pub best_loss: Option<f64>, //Over the current population
+    pub mean_loss: Option<f64>,
+    pub median_loss: Option<f64>,
+    pub best_so_far: Option<f64>, //Over the whole run
+    pub diversity: f64,
+    pub mean_node_count: f64,
+    pub mean_connection_count: f64,
+    pub mean_depth: f64,
+    pub node_type_usage: BTreeMap<usize, usize>, //Amount of nodes of each type over the whole population
+}
+
+impl GenerationMetrics {
+    pub fn measure(trainer: &Trainer) -> GenerationMetrics {
+        let mut finite_losses: Vec<f64> = trainer.losses().iter().copied().filter(|loss| loss.is_finite()).collect();
+        finite_losses.sort_by(f64::total_cmp);
+        let median_loss = match finite_losses.len() {
+            0 => None,
+            length if length % 2 == 0 => Some((finite_losses[length / 2 - 1] + finite_losses[length / 2]) / 2.0),
+            length => Some(finite_losses[length / 2]),
+        };
+
+        let population = trainer.population();
+        let mut node_count = 0;
+        let mut connection_count = 0;
+        let mut depth = 0;
+        let mut node_type_usage = BTreeMap::new();
+        for network in population {
+            let complexity = network.complexity();
+            node_count += complexity.node_count;
+            connection_count += complexity.connection_count;
+            depth += complexity.depth;
+            for (node_type, count) in network.node_type_counts() {
+                *node_type_usage.entry(node_type).or_insert(0) += count;
+            }
+        }
+        let network_count = population.len().max(1) as f64;
+
+        GenerationMetrics {
+            generation: trainer.generation(),
+            best_loss: finite_losses.first().copied(),
+            mean_loss: if finite_losses.is_empty() { None } else { Some(finite_losses.iter().sum::<f64>() / finite_losses.len() as f64) },
+            median_loss,
+            best_so_far: trainer.best().map(|(_, loss)| loss).filter(|loss| loss.is_finite()),
+            diversity: diversity(population),
+            mean_node_count: node_count as f64 / network_count,
+            mean_connection_count: connection_count as f64 / network_count,
+            mean_depth: depth as f64 / network_count,
+            node_type_usage,
+        }
+    } //Measures the current population, the losses are only known between evaluating and breeding (see TrainerHooks::on_evaluated)
+
+    pub fn to_json(&self) -> String {
+        let node_type_usage: Vec<String> = self.node_type_usage.iter().map(|(id, count)| format!("\"{}\":{}", id, count)).collect();
+        format!(
+            "{{\"generation\":{},\"best_loss\":{},\"mean_loss\":{},\"median_loss\":{},\"best_so_far\":{},\"diversity\":{},\"mean_node_count\":{},\"mean_connection_count\":{},\"mean_depth\":{},\"node_type_usage\":{{{}}}}}",
+            self.generation,
+            json_number(self.best_loss),
+            json_number(self.mean_loss),
+            json_number(self.median_loss),
+            json_number(self.best_so_far),
+            json_number(Some(self.diversity)),
+            json_number(Some(self.mean_node_count)),
+            json_number(Some(self.mean_connection_count)),
+            json_number(Some(self.mean_depth)),
+            node_type_usage.join(","),
+        )
+    } //One JSON object on a single line
+}
+
+fn json_number(value: Option<f64>) -> String {
+    match value {
+        Some(value) if value.is_finite() => value.to_string(),
+        _ => String::from("null"),
+    }
+} //JSON has no infinity or NaN
+
+pub fn diversity(population: &[Network]) -> f64 {
+    let coefficients = SpeciationSettings::default().coefficients;
+    let mut total = 0.0;
+    let mut pair_count = 0;
+    for (index, first) in population.iter().enumerate() {
+        for second in &population[index + 1..] {
+            total += speciation::structural_distance(first, second, &coefficients);
+            pair_count += 1;
+        }
+    }
+    if pair_count == 0 { 0.0 } else { total / pair_count as f64 }
+} //Mean structural distance between every pair of networks
+
+#[derive(Debug)]
+pub struct MetricsLog<W: Write> {
+    writer: W,
+}
+
+impl MetricsLog<std::fs::File> {
+    pub fn create(path: &str) -> Result<MetricsLog<std::fs::File>, Error> {
+        std::fs::File::create(path).map(MetricsLog::new).map_err(|_| Error::FileWrite)
+    } //Starts a new log file, replacing any file already at the path
+}
+
+impl<W: Write> MetricsLog<W> {
+    pub fn new(writer: W) -> MetricsLog<W> {
+        MetricsLog { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> TrainerHooks for MetricsLog<W> {
+    fn on_evaluated(&mut self, trainer: &Trainer) -> Result<(), Error> {
+        writeln!(self.writer, "{}", GenerationMetrics::measure(trainer).to_json()).map_err(|_| Error::FileWrite)
+    }
+
+    fn on_finish(&mut self, _trainer: &Trainer) -> Result<(), Error> {
+        self.writer.flush().map_err(|_| Error::FileWrite)
+    }
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_models::dannn::NodeSelectionTable;
+    use crate::core_models::random::Rng;
+    use crate::core_models::trainer::TrainerSettings;
+
+    fn trainer() -> Trainer {
+        let mut table = NodeSelectionTable::new();
+        table.enable_simple_arithmetic();
+        let settings = TrainerSettings { population_size: 10, input_count: 2, seed: 7, ..TrainerSettings::default() };
+        Trainer::new(settings, table).unwrap()
+    }
+
+    #[test]
+    fn metrics_log_writes_one_line_per_generation() {
+        let mut log = MetricsLog::new(Vec::new());
+        trainer().train_with_hooks(4, |network: &mut Network, _: &mut Rng| network.node_count() as f64, &mut log).unwrap();
+        let text = String::from_utf8(log.into_inner()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        for (generation, line) in lines.iter().enumerate() {
+            assert!(line.starts_with(&format!("{{\"generation\":{},\"best_loss\":", generation)));
+            assert!(line.contains("\"node_type_usage\":{\"2"));
+            assert!(line.ends_with("}}"));
+        }
+    }
+
+    #[test]
+    fn metrics_log_works_in_every_mode() {
+        use crate::core_models::map_elites::{Archive, BehaviorDimension};
+        let size = |network: &mut Network, _: &mut Rng| (network.node_count() as f64, vec![network.complexity().connection_count as f64]);
+        let dimensions = vec![BehaviorDimension { name: String::from("connections"), minimum: 0.0, maximum: 10.0, bins: 5 }];
+        let mut logs = vec![MetricsLog::new(Vec::new()), MetricsLog::new(Vec::new()), MetricsLog::new(Vec::new())];
+        trainer().train_multi_objective_with_hooks(3, |network: &mut Network, _: &mut Rng| vec![network.node_count() as f64, 1.0], &mut logs[0]).unwrap();
+        trainer().train_novelty_with_hooks(3, size, &mut logs[1]).unwrap();
+        trainer().train_map_elites_with_hooks(3, &mut Archive::new(dimensions), size, &mut logs[2]).unwrap();
+        for log in logs {
+            let text = String::from_utf8(log.into_inner()).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines.len(), 3);
+            for (generation, line) in lines.iter().enumerate() {
+                assert!(line.starts_with(&format!("{{\"generation\":{},\"best_loss\":", generation)));
+                assert!(!line.contains("\"best_so_far\":null"));
+            }
+        }
+    }
+
+    #[test]
+    fn metrics_of_population() {
+        let mut trainer = trainer();
+        let mut loss = 0.0;
+        trainer.step(&mut |network: &mut Network, _: &mut Rng| network.node_count() as f64);
+        trainer.evaluate_population(&mut |_: &mut Network, _: &mut Rng| {
+            loss += 1.0;
+            if loss > 9.0 { f64::INFINITY } else { loss }
+        });
+        let metrics = GenerationMetrics::measure(&trainer);
+        assert_eq!(metrics.generation, 1);
+        assert_eq!(metrics.best_loss, Some(1.0));
+        assert_eq!(metrics.mean_loss, Some(5.0));
+        assert_eq!(metrics.median_loss, Some(5.0));
+        assert_eq!(metrics.node_type_usage.values().sum::<usize>() as f64, metrics.mean_node_count * 10.0);
+        assert!(metrics.diversity >= 0.0);
+        assert_eq!(diversity(&trainer.population()[..1]), 0.0);
+        assert!(GenerationMetrics { best_so_far: Some(f64::INFINITY), ..metrics }.to_json().contains("\"best_so_far\":null"));
+    }
+}
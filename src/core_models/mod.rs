@@ -1,2 +1,15 @@
+pub mod config;
+pub mod constant_optimization;
 pub mod dannn;
-
+pub mod dataset;
+pub mod export;
+pub mod fitness;
+pub mod map_elites;
+pub mod metrics;
+pub mod novelty;
+pub mod pareto;
+pub mod profile;
+pub mod random;
+pub mod serialization;
+pub mod speciation;
+pub mod trainer;
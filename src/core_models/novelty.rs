@@ -0,0 +1,124 @@
+use crate::core_models::dannn::{Network, Value};
+use crate::core_models::dataset::Dataset;
+use crate::core_models::random::Rng;
+
+/*
+Novelty search rewards networks for behaving differently from what has been seen before, instead of (or as well as)
+for having a low loss, which helps on deceptive problems where the path to a good network goes through bad ones.
+
+A behavior is a vector describing what a network does, usually its outputs over a fixed set of inputs. The novelty of
+a behavior is its mean distance to the k nearest behaviors in the current population and in the novelty archive. The
+archive remembers behaviors that were novel enough when they were found, so the search doesn't circle back to them.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoveltySettings {
+    pub nearest_neighbors: usize, //The k in k nearest neighbors
+    pub archive_threshold: f64, //Behaviors with a novelty above this are added to the archive
+    pub max_archive_size: usize, //The oldest behaviors are dropped once the archive is full
+    pub novelty_weight: f64, //0 selects on loss only, 1 selects on novelty only, anything between mixes the two
+}
+
+impl Default for NoveltySettings {
+    fn default() -> NoveltySettings {
+        NoveltySettings {
+            nearest_neighbors: 15,
+            archive_threshold: 1.0,
+            max_archive_size: 1000,
+            novelty_weight: 1.0,
+        }
+    }
+}
+
+pub fn behavior_distance(first: &[f64], second: &[f64]) -> f64 {
+    let mut total = 0.0;
+    for index in 0..first.len().max(second.len()) {
+        let difference = first.get(index).unwrap_or(&0.0) - second.get(index).unwrap_or(&0.0);
+        total += difference * difference;
+    }
+    total.sqrt()
+} //Euclidean distance, a shorter behavior is padded with zeros
+
+pub fn novelty_scores(behaviors: &[Vec<f64>], archive: &[Vec<f64>], nearest_neighbors: usize) -> Vec<f64> {
+    behaviors.iter().enumerate().map(|(index, behavior)| {
+        let mut distances: Vec<f64> = behaviors.iter().enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .map(|(_, other)| behavior_distance(behavior, other))
+            .chain(archive.iter().map(|other| behavior_distance(behavior, other)))
+            .filter(|distance| !distance.is_nan())
+            .collect();
+        if distances.is_empty() {
+            return 0.0;
+        }
+        distances.sort_by(f64::total_cmp);
+        let neighbors = nearest_neighbors.clamp(1, distances.len());
+        distances[..neighbors].iter().sum::<f64>() / neighbors as f64
+    }).collect()
+} //Novelty of each behavior against the rest of the behaviors and the archive
+
+pub fn update_archive(archive: &mut Vec<Vec<f64>>, behaviors: &[Vec<f64>], scores: &[f64], settings: &NoveltySettings) {
+    for (behavior, score) in behaviors.iter().zip(scores) {
+        if *score > settings.archive_threshold {
+            archive.push(behavior.clone());
+        }
+    }
+    if archive.len() > settings.max_archive_size {
+        let excess = archive.len() - settings.max_archive_size;
+        archive.drain(..excess);
+    }
+} //Adds novel behaviors to the archive, dropping the oldest ones past the size limit
+
+pub fn hybrid_losses(losses: &[f64], scores: &[f64], novelty_weight: f64) -> Vec<f64> {
+    //Both are scaled to 0..1 over the population first, so the weight doesn't depend on the size of either
+    let scale = |values: &[f64]| -> Vec<f64> {
+        let finite = values.iter().copied().filter(|value| value.is_finite());
+        let lowest = finite.clone().fold(f64::INFINITY, f64::min);
+        let highest = finite.fold(f64::NEG_INFINITY, f64::max);
+        values.iter().map(|value| {
+            if !value.is_finite() {
+                return if *value > 0.0 { 1.0 } else { 0.0 };
+            }
+            if highest > lowest { (value - lowest) / (highest - lowest) } else { 0.0 }
+        }).collect()
+    };
+    let scaled_losses = scale(losses);
+    let scaled_scores = scale(scores);
+    scaled_losses.iter().zip(scaled_scores).map(|(loss, score)| (1.0 - novelty_weight) * loss + novelty_weight * (1.0 - score)).collect()
+} //Mixes losses and novelty into one loss for selection, lower is better
+
+pub fn output_behavior(network: &mut Network, dataset: &Dataset, rng: &mut Rng) -> Vec<f64> {
+    match dataset.evaluate(network, rng) {
+        Ok(outputs) => outputs.iter().flatten().map(Value::as_number).collect(),
+        Err(_) => Vec::new(),
+    }
+} //A ready made behavior: every output of the network over every row of a dataset
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_neighbor_novelty() {
+        let behaviors = vec![vec![0.0], vec![1.0], vec![10.0]];
+        assert_eq!(novelty_scores(&behaviors, &[], 1), vec![1.0, 1.0, 9.0]);
+        assert_eq!(novelty_scores(&behaviors, &[vec![10.5]], 1), vec![1.0, 1.0, 0.5]);
+        assert_eq!(novelty_scores(&behaviors, &[], 5), vec![5.5, 5.0, 9.5]); //k is limited to the available neighbors
+        assert_eq!(behavior_distance(&[3.0], &[0.0, 4.0]), 5.0);
+    }
+
+    #[test]
+    fn archive_and_hybrid_weighting() {
+        let settings = NoveltySettings { archive_threshold: 2.0, max_archive_size: 2, ..NoveltySettings::default() };
+        let mut archive = vec![vec![-1.0]];
+        update_archive(&mut archive, &[vec![0.0], vec![5.0], vec![9.0]], &[1.0, 3.0, 4.0], &settings);
+        assert_eq!(archive, vec![vec![5.0], vec![9.0]]);
+
+        let losses = [0.0, 10.0, f64::INFINITY];
+        let scores = [1.0, 3.0, 2.0];
+        assert_eq!(hybrid_losses(&losses, &scores, 0.0), vec![0.0, 1.0, 1.0]);
+        assert_eq!(hybrid_losses(&losses, &scores, 1.0), vec![1.0, 0.0, 0.5]);
+        assert_eq!(hybrid_losses(&losses, &scores, 0.5), vec![0.5, 0.5, 0.75]);
+    }
+}
@@ -0,0 +1,128 @@
+/*
+Pareto helpers for multi-objective training (NSGA-II). Every objective is minimized, like the losses in the
+fitness module. One objective vector dominates another when it is no worse in every objective and strictly better in
+at least one. The first front is every vector that nothing dominates, the second front is what nothing outside of the
+first front dominates, and so on.
+ */
+
+pub fn dominates(first: &[f64], second: &[f64]) -> bool {
+    let mut strictly_better = false;
+    for (first_objective, second_objective) in first.iter().zip(second) {
+        if first_objective > second_objective {
+            return false;
+        }
+        if first_objective < second_objective {
+            strictly_better = true;
+        }
+    }
+    strictly_better
+}
+
+pub fn non_dominated_sort(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); objectives.len()]; //Vectors that each vector dominates
+    let mut domination_counts = vec![0; objectives.len()]; //Amount of vectors dominating each vector
+    for first in 0..objectives.len() {
+        for second in (first + 1)..objectives.len() {
+            if dominates(&objectives[first], &objectives[second]) {
+                dominated_by[first].push(second);
+                domination_counts[second] += 1;
+            } else if dominates(&objectives[second], &objectives[first]) {
+                dominated_by[second].push(first);
+                domination_counts[first] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..objectives.len()).filter(|index| domination_counts[*index] == 0).collect();
+    while !front.is_empty() {
+        let mut next_front = Vec::new();
+        for index in &front {
+            for dominated in &dominated_by[*index] {
+                domination_counts[*dominated] -= 1;
+                if domination_counts[*dominated] == 0 {
+                    next_front.push(*dominated);
+                }
+            }
+        }
+        next_front.sort_unstable();
+        fronts.push(front);
+        front = next_front;
+    }
+    fronts
+} //Groups the indices of objective vectors into fronts, best front first
+
+pub fn crowding_distance(objectives: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    //Distance is measured in each objective between a vectors neighbours in the front, the ends of a front are infinitely far
+    let mut distances = vec![0.0; front.len()];
+    if front.len() <= 2 {
+        return vec![f64::INFINITY; front.len()];
+    }
+    let objective_count = objectives[front[0]].len();
+    for objective in 0..objective_count {
+        let values = objective_values(objectives, front, objective);
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|first, second| values[*first].total_cmp(&values[*second]));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+        let span = values[last] - values[first];
+        if span <= 0.0 || !span.is_finite() {
+            continue;
+        }
+        for position in 1..order.len() - 1 {
+            distances[order[position]] += (values[order[position + 1]] - values[order[position - 1]]) / span;
+        }
+    }
+    distances
+} //Crowding distance of each member of a front, in the same order as the front
+
+fn objective_values(objectives: &[Vec<f64>], front: &[usize], objective: usize) -> Vec<f64> {
+    front.iter().map(|index| objectives[*index][objective]).collect()
+}
+
+pub fn rank_and_crowding(objectives: &[Vec<f64>]) -> (Vec<usize>, Vec<f64>) {
+    let mut ranks = vec![0; objectives.len()];
+    let mut distances = vec![0.0; objectives.len()];
+    for (rank, front) in non_dominated_sort(objectives).iter().enumerate() {
+        for (position, distance) in crowding_distance(objectives, front).into_iter().enumerate() {
+            ranks[front[position]] = rank;
+            distances[front[position]] = distance;
+        }
+    }
+    (ranks, distances)
+} //Front rank and crowding distance of every objective vector
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domination() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[0.0, 3.0], &[1.0, 2.0]));
+    }
+
+    #[test]
+    fn fronts_and_crowding() {
+        let objectives = vec![
+            vec![1.0, 5.0],
+            vec![2.0, 3.0],
+            vec![4.0, 1.0],
+            vec![3.0, 4.0], //Dominated by [2, 3]
+            vec![5.0, 5.0], //Dominated by everything else
+        ];
+        assert_eq!(non_dominated_sort(&objectives), vec![vec![0, 1, 2], vec![3], vec![4]]);
+
+        let distances = crowding_distance(&objectives, &[0, 1, 2]);
+        assert_eq!(distances[0], f64::INFINITY);
+        assert_eq!(distances[2], f64::INFINITY);
+        assert_eq!(distances[1], 1.0 + 1.0);
+
+        let (ranks, _) = rank_and_crowding(&objectives);
+        assert_eq!(ranks, vec![0, 0, 0, 1, 2]);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::*;
+use serde_json::json;
+use crate::core_models::dannn::{Error, NodeSelectionTable};
+
+/*
+A profile is a named NodeSelectionTable saved to a file, so a set of node types can be reused between runs without
+writing enable/disable calls. Only the enabled node types and the prevalences that differ from their defaults are
+saved. Profiles can be written as TOML:
+
+    name = "symbolic regression"
+    enabled = [113, 201, 202, 203, 204]
+
+    [prevalence]
+    204 = 0.5
+
+or as the same fields in JSON:
+
+    {"name": "symbolic regression", "enabled": [113, 201, 202, 203, 204], "prevalence": {"204": 0.5}}
+
+A few presets are built in, see PRESETS. A training config can start from a preset or a profile file with the
+"profile" key of its [nodes] table.
+ */
+
+pub const PRESETS: [&str; 3] = ["boolean logic only", "symbolic regression", "string processing"];
+
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub node_selection_table: NodeSelectionTable,
+}
+
+impl Profile {
+    pub fn new(name: &str, node_selection_table: NodeSelectionTable) -> Profile {
+        Profile {
+            name: name.to_string(),
+            node_selection_table,
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Profile> {
+        let mut table = NodeSelectionTable::new();
+        let mut prevalences: Vec<(usize, f32)> = Vec::new();
+        match name {
+            "boolean logic only" => table.enable_logic(),
+            "symbolic regression" => {
+                table.enable_simple_arithmetic();
+                table.enable_advanced_arithmetic();
+                table.enable_by_id(113);
+                prevalences = vec![(209, 0.5), (214, 0.3), (215, 0.3), (216, 0.3), (222, 0.2), (223, 0.2), (224, 0.2)]; //Tangent, rounding and reciprocal trig functions rarely help a formula
+            }
+            "string processing" => {
+                table.enable_string_modification();
+                table.enable_by_id(107); //Equality Test
+                table.enable_by_id(113);
+                table.enable_by_id(201);
+                prevalences = vec![(107, 0.5), (201, 0.5)];
+            }
+            _ => return None,
+        }
+        for (id, prevalence) in prevalences {
+            table.set_prevalence_by_id(id, prevalence).ok()?;
+        }
+        Some(Profile::new(name, table))
+    } //One of the built in PRESETS
+
+    fn enabled_and_overrides(&self) -> (Vec<usize>, BTreeMap<usize, f32>) {
+        let mut overrides = BTreeMap::new();
+        for descriptor in self.node_selection_table.descriptors() {
+            if NodeSelectionTable::template_descriptor(descriptor.id()).is_none_or(|template| template.prevalence != descriptor.prevalence()) {
+                overrides.insert(descriptor.id(), descriptor.prevalence());
+            }
+        }
+        (self.node_selection_table.enabled_ids(), overrides)
+    } //Enabled node types, and the prevalences that differ from the default
+
+    fn from_parts(name: String, enabled: Vec<usize>, overrides: Vec<(usize, f64)>) -> Result<Profile, Error> {
+        let mut table = NodeSelectionTable::new();
+        for id in enabled {
+            if NodeSelectionTable::template_descriptor(id).is_none() {
+                return Err(Error::InvalidNodeType);
+            }
+            table.enable_by_id(id);
+        }
+        for (id, prevalence) in overrides {
+            table.set_prevalence_by_id(id, prevalence as f32)?; //Fails for node types that aren't enabled
+        }
+        Ok(Profile::new(&name, table))
+    }
+
+    pub fn to_toml(&self) -> String {
+        let (enabled, overrides) = self.enabled_and_overrides();
+        let mut text = format!("name = \"{}\"\n", self.name.replace('\\', "\\\\").replace('"', "\\\""));
+        text.push_str(&format!("enabled = [{}]\n", enabled.iter().map(usize::to_string).collect::<Vec<String>>().join(", ")));
+        if !overrides.is_empty() {
+            text.push_str("\n[prevalence]\n");
+            for (id, prevalence) in overrides {
+                text.push_str(&format!("{} = {}\n", id, prevalence));
+            }
+        }
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        let (enabled, overrides) = self.enabled_and_overrides();
+        let prevalence: serde_json::Map<String, serde_json::Value> = overrides.iter()
+            .map(|(id, prevalence)| (id.to_string(), json!(prevalence.to_string().parse::<f64>().unwrap_or(0.0)))) //Through text so 0.3 stays 0.3 instead of the f64 closest to the f32
+            .collect();
+        let value = json!({ "name": self.name, "enabled": enabled, "prevalence": prevalence });
+        serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Profile, Error> {
+        let table: toml::Table = text.parse().map_err(|_| Error::ProfileParse)?;
+        if table.keys().any(|key| !["name", "enabled", "prevalence"].contains(&key.as_str())) {
+            return Err(Error::ProfileParse);
+        }
+        let name = table.get("name").and_then(toml::Value::as_str).ok_or(Error::ProfileParse)?.to_string();
+        let enabled = table.get("enabled").and_then(toml::Value::as_array).ok_or(Error::ProfileParse)?.iter()
+            .map(|id| id.as_integer().and_then(|id| usize::try_from(id).ok()).ok_or(Error::ProfileParse))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        let mut overrides = Vec::new();
+        if let Some(prevalence) = table.get("prevalence") {
+            for (id, value) in prevalence.as_table().ok_or(Error::ProfileParse)? {
+                let value = value.as_float().or(value.as_integer().map(|value| value as f64)).ok_or(Error::ProfileParse)?;
+                overrides.push((id.parse().map_err(|_| Error::ProfileParse)?, value));
+            }
+        }
+        Profile::from_parts(name, enabled, overrides)
+    }
+
+    pub fn from_json_str(text: &str) -> Result<Profile, Error> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|_| Error::ProfileParse)?;
+        let object = value.as_object().ok_or(Error::ProfileParse)?;
+        if object.keys().any(|key| !["name", "enabled", "prevalence"].contains(&key.as_str())) {
+            return Err(Error::ProfileParse);
+        }
+        let name = object.get("name").and_then(serde_json::Value::as_str).ok_or(Error::ProfileParse)?.to_string();
+        let enabled = object.get("enabled").and_then(serde_json::Value::as_array).ok_or(Error::ProfileParse)?.iter()
+            .map(|id| id.as_u64().map(|id| id as usize).ok_or(Error::ProfileParse))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        let mut overrides = Vec::new();
+        if let Some(prevalence) = object.get("prevalence") {
+            for (id, value) in prevalence.as_object().ok_or(Error::ProfileParse)? {
+                overrides.push((id.parse().map_err(|_| Error::ProfileParse)?, value.as_f64().ok_or(Error::ProfileParse)?));
+            }
+        }
+        Profile::from_parts(name, enabled, overrides)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let text = if path.ends_with(".json") { self.to_json() } else { self.to_toml() };
+        std::fs::write(path, text).map_err(|_| Error::FileWrite)
+    } //Saves as JSON if the path ends with .json, as TOML otherwise
+
+    pub fn load(path: &str) -> Result<Profile, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::FileRead)?;
+        if path.ends_with(".json") {
+            return Profile::from_json_str(&text);
+        }
+        Profile::from_toml_str(&text)
+    } //Loads JSON if the path ends with .json, TOML otherwise
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_prevalences(profile: &Profile) -> BTreeMap<usize, f32> {
+        profile.node_selection_table.descriptors().iter().map(|descriptor| (descriptor.id(), descriptor.prevalence())).collect()
+    }
+
+    #[test]
+    fn profile_round_trips() {
+        let mut table = NodeSelectionTable::new();
+        table.enable_simple_arithmetic();
+        table.enable_by_id(113);
+        table.set_prevalence_by_id(204, 0.3).unwrap();
+        let profile = Profile::new("arithmetic \"mostly\"", table);
+
+        let toml = profile.to_toml();
+        assert_eq!(toml, "name = \"arithmetic \\\"mostly\\\"\"\nenabled = [113, 201, 202, 203, 204]\n\n[prevalence]\n204 = 0.3\n");
+        let json = profile.to_json();
+        assert!(json.contains("\"204\": 0.3\n"));
+
+        for loaded in [Profile::from_toml_str(&toml).unwrap(), Profile::from_json_str(&json).unwrap()] {
+            assert_eq!(loaded.name, profile.name);
+            assert_eq!(enabled_prevalences(&loaded), enabled_prevalences(&profile));
+        }
+    }
+
+    #[test]
+    fn presets_and_invalid_profiles() {
+        for name in PRESETS {
+            let preset = Profile::preset(name).unwrap();
+            assert_eq!(preset.name, name);
+            let loaded = Profile::from_toml_str(&preset.to_toml()).unwrap();
+            assert_eq!(enabled_prevalences(&loaded), enabled_prevalences(&preset));
+        }
+        assert!(Profile::preset("everything").is_none());
+        assert_eq!(enabled_prevalences(&Profile::preset("boolean logic only").unwrap()).keys().copied().collect::<Vec<usize>>(), (101..=117).collect::<Vec<usize>>());
+
+        assert!(Profile::from_toml_str("name = \"x\"\nenabled = [999]\n").is_err());
+        assert!(Profile::from_toml_str("name = \"x\"\nenabled = [201]\n[prevalence]\n202 = 0.5\n").is_err()); //Not enabled
+        assert!(Profile::from_json_str("{\"name\": \"x\", \"enabled\": [201], \"prevalence\": {\"201\": 2}}").is_err());
+        assert!(Profile::from_json_str("{\"name\": \"x\", \"enabled\": [201], \"colour\": 1}").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+/*
+A small seeded random number generator (splitmix64). Everything random in the crate draws from one of these
+instead of an outside crate, so that a seed always reproduces the same run, on any machine.
+ */
+
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng {
+            state: seed,
+        }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    } //Rng::new(rng.state()) continues exactly where rng is
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    } //Uniform float in [0, 1)
+
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        if high <= low {
+            return low;
+        }
+        low + (self.next_u64() % (high - low) as u64) as usize
+    } //Uniform integer in [low, high)
+
+    pub fn next_gaussian(&mut self) -> f64 {
+        let first = 1.0 - self.next_f64(); //In (0, 1] so the logarithm is finite
+        let second = self.next_f64();
+        (-2.0 * first.ln()).sqrt() * (2.0 * std::f64::consts::PI * second).cos()
+    } //Standard normal sample (Box-Muller)
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.range(0, index + 1);
+            items.swap(index, other);
+        }
+    } //Fisher-Yates shuffle
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn seeded_rng_is_reproducible() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        for _ in 0..100 {
+            let value = first.next_f64();
+            assert!((0.0..1.0).contains(&value));
+            assert!(first.range(3, 7) >= 3 && first.range(3, 7) < 7);
+        }
+    }
+
+    #[test]
+    fn gaussian_samples() {
+        let mut rng = Rng::new(3);
+        let samples: Vec<f64> = (0..10000).map(|_| rng.next_gaussian()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.05);
+        assert!((variance - 1.0).abs() < 0.05);
+    }
+}
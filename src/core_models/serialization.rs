@@ -0,0 +1,154 @@
+use crate::core_models::dannn::{ConnectionType, Error, Network};
+
+/*
+Networks are saved as plain text, one item per line, so saved models can be read and diffed by hand:
+
+    layer
+    node 201
+    connection input 0
+    connection constant 1.5
+    layer
+    node 104
+    connection node 0 0
+
+Each "layer" line starts a new layer, each "node" line adds a node of that type to the last layer, and each
+"connection" line connects something to the last node. A memory node that remembers something (see run_memory_node)
+is followed by a "memory 2.5" line, so a model saved in the middle of an episode carries on where it was. Numbers are
+written with Rust's shortest round trip formatting, so a saved network loads back with exactly the same constants.
+Blank lines and lines starting with # are ignored.
+
+There is no simplification pass (folding constants or dropping unused nodes) yet. One would have to keep the memory
+nodes (701 to 704) that an output depends on even when all their inputs are constant, since their output still
+changes from row to row.
+ */
+
+pub fn network_to_text(network: &Network) -> String {
+    let mut text = String::new();
+    for layer in &network.layers {
+        text.push_str("layer\n");
+        for node in &layer.nodes {
+            text.push_str(&format!("node {}\n", node.node_type_id));
+            for connection in &node.connections {
+                match connection {
+                    ConnectionType::Connection(node_connection) => text.push_str(&format!("connection node {} {}\n", node_connection.source_layer, node_connection.source_node)),
+                    ConnectionType::Input(input) => text.push_str(&format!("connection input {}\n", input.input_id)),
+                    ConnectionType::Constant(constant) => text.push_str(&format!("connection constant {}\n", constant.value)),
+                }
+            }
+            if node.memory != 0.0 {
+                text.push_str(&format!("memory {}\n", node.memory));
+            }
+        }
+    }
+    text
+}
+
+pub fn network_from_text(text: &str) -> Result<Network, Error> {
+    let mut network = Network::new();
+    for line in text.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            [comment, ..] if comment.starts_with('#') => {}
+            ["layer"] => {
+                let _ = network.generate_layer_to_network();
+            }
+            ["node", node_type] => {
+                let layer_index = network.layers.len().checked_sub(1).ok_or(Error::ModelParse)?;
+                network.generate_node_to_layer(parse(node_type)?, layer_index)?;
+            }
+            ["memory", value] => {
+                let layer_index = network.layers.len().checked_sub(1).ok_or(Error::ModelParse)?;
+                let node = network.layers[layer_index].nodes.last_mut().ok_or(Error::ModelParse)?;
+                node.memory = parse(value)?;
+            }
+            ["connection", kind, arguments @ ..] => {
+                let layer_index = network.layers.len().checked_sub(1).ok_or(Error::ModelParse)?;
+                let node_index = network.layers[layer_index].nodes.len().checked_sub(1).ok_or(Error::ModelParse)?;
+                match (*kind, arguments) {
+                    ("node", [source_layer, source_node]) => network.connect_node(layer_index, node_index, parse(source_layer)?, parse(source_node)?)?,
+                    ("input", [input_id]) => network.connect_input(layer_index, node_index, parse(input_id)?)?,
+                    ("constant", [value]) => network.connect_constant(layer_index, node_index, parse(value)?)?,
+                    _ => return Err(Error::ModelParse),
+                }
+            }
+            _ => return Err(Error::ModelParse),
+        }
+    }
+    Ok(network)
+}
+
+pub(crate) fn read_network_block<'a, I>(lines: &mut I) -> Result<Network, Error> where I: Iterator<Item = &'a str> {
+    let mut network_text = String::new();
+    loop {
+        match lines.next() {
+            Some("end") => break,
+            Some(line) => {
+                network_text.push_str(line);
+                network_text.push('\n');
+            }
+            None => return Err(Error::ModelParse),
+        }
+    }
+    network_from_text(&network_text)
+} //Reads a network embedded in a larger file, up to a line holding only "end"
+
+pub(crate) fn write_atomically(path: &str, text: &str) -> Result<(), Error> {
+    let temporary_path = format!("{}.tmp", path);
+    std::fs::write(&temporary_path, text).map_err(|_| Error::FileWrite)?;
+    std::fs::rename(&temporary_path, path).map_err(|_| Error::FileWrite)
+} //Writes next to the path and then renames over it, so a crash while saving never leaves a half written file
+
+pub fn save_network(network: &Network, path: &str) -> Result<(), Error> {
+    std::fs::write(path, network_to_text(network)).map_err(|_| Error::FileWrite)
+}
+
+pub fn load_network(path: &str) -> Result<Network, Error> {
+    let text = std::fs::read_to_string(path).map_err(|_| Error::FileRead)?;
+    network_from_text(&text)
+}
+
+pub(crate) fn parse<T: std::str::FromStr>(word: &str) -> Result<T, Error> {
+    word.parse().map_err(|_| Error::ModelParse)
+}
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_text_round_trip() {
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(201, 0);
+        let _ = network.generate_node_to_layer(104, 1);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_constant(0, 0, 0.1 + 0.2).unwrap();
+        network.connect_node(1, 0, 0, 0).unwrap();
+
+        let text = network_to_text(&network);
+        assert_eq!(text, "layer\nnode 201\nconnection input 0\nconnection constant 0.30000000000000004\nlayer\nnode 104\nconnection node 0 0\n");
+        let loaded = network_from_text(&format!("# saved network\n{}", text)).unwrap();
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", network));
+    }
+
+    #[test]
+    fn malformed_network_text() {
+        assert!(network_from_text("node 201\n").is_err()); //Node before any layer
+        assert!(network_from_text("layer\nnode x\n").is_err());
+        assert!(network_from_text("layer\nnode 201\nconnection node 0 0\n").is_err()); //Does not feed forward
+        assert!(network_from_text("layer\nnode 201\nconnection wire 1\n").is_err());
+        assert!(network_from_text("memory 1\n").is_err()); //Memory before any node
+    }
+
+    #[test]
+    fn memory_round_trip() {
+        let text = "layer\nnode 702\nconnection input 0\nmemory 2.5\nnode 701\nconnection input 0\n";
+        let network = network_from_text(text).unwrap();
+        assert_eq!(network.layers[0].nodes[0].memory, 2.5);
+        assert_eq!(network_to_text(&network), text); //Nodes that remember nothing get no memory line
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::*;
+use crate::core_models::dannn::{ConnectionType, Network};
+
+/*
+NEAT-style speciation. Networks are grouped into species by a structural distance, and networks only compete
+for selection against the size of their own species (fitness sharing), so that a new topology gets a few generations
+to improve before it has to beat the best network of the whole population. A species that has not improved its best
+loss for a while is stagnant and stops breeding.
+
+The distance between two networks is a weighted sum of three parts:
+ - node types: how many nodes would have to change type, be added, or be removed, layer by layer
+ - connections: how many connections one network has that the other doesn't
+ - constants: the mean difference between constants that are connected to the same place in both networks
+The first two parts are divided by the size of the larger network, so the distance does not grow with network size.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceCoefficients {
+    pub node_type: f64,
+    pub connection: f64,
+    pub constant: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeciationSettings {
+    pub coefficients: DistanceCoefficients,
+    pub threshold: f64, //Networks closer than this to a species representative join that species
+    pub stagnation_limit: usize, //Generations without improvement before a species stops breeding
+}
+
+impl Default for SpeciationSettings {
+    fn default() -> SpeciationSettings {
+        SpeciationSettings {
+            coefficients: DistanceCoefficients {
+                node_type: 1.0,
+                connection: 1.0,
+                constant: 0.4,
+            },
+            threshold: 1.0,
+            stagnation_limit: 15,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Species {
+    pub(crate) id: usize,
+    pub(crate) representative: Network, //New networks are compared against this network to decide if they belong to the species
+    pub(crate) members: Vec<usize>, //Indices of the members in the trainers population
+    pub(crate) best_loss: f64,
+    pub(crate) last_improved_generation: usize,
+}
+
+impl Species {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn members(&self) -> &[usize] {
+        &self.members
+    }
+
+    pub fn best_loss(&self) -> f64 {
+        self.best_loss
+    }
+
+    pub fn stagnant_generations(&self, generation: usize) -> usize {
+        generation.saturating_sub(self.last_improved_generation)
+    } //Generations since the best loss of the species last improved
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ConnectionKey { //A connection described by where it goes and where it comes from, constants only by where they go
+    Node(usize, usize, usize, usize),
+    Input(usize, usize, usize),
+    Constant(usize, usize),
+}
+
+fn connection_keys(network: &Network) -> HashMap<ConnectionKey, usize> {
+    let mut keys = HashMap::new();
+    for (layer_index, layer) in network.layers.iter().enumerate() {
+        for (node_index, node) in layer.nodes.iter().enumerate() {
+            for connection in &node.connections {
+                let key = match connection {
+                    ConnectionType::Connection(node_connection) => ConnectionKey::Node(layer_index, node_index, node_connection.source_layer, node_connection.source_node),
+                    ConnectionType::Input(input) => ConnectionKey::Input(layer_index, node_index, input.input_id),
+                    ConnectionType::Constant(_) => ConnectionKey::Constant(layer_index, node_index),
+                };
+                *keys.entry(key).or_insert(0) += 1;
+            }
+        }
+    }
+    keys
+} //Counts each distinct connection, duplicates are allowed so this is a multiset
+
+fn constants(network: &Network) -> HashMap<(usize, usize, usize), f64> {
+    let mut constants = HashMap::new();
+    for (layer_index, layer) in network.layers.iter().enumerate() {
+        for (node_index, node) in layer.nodes.iter().enumerate() {
+            for (connection_index, connection) in node.connections.iter().enumerate() {
+                if let ConnectionType::Constant(constant) = connection {
+                    constants.insert((layer_index, node_index, connection_index), constant.value);
+                }
+            }
+        }
+    }
+    constants
+}
+
+pub fn structural_distance(first: &Network, second: &Network, coefficients: &DistanceCoefficients) -> f64 {
+    let mut node_type_difference = 0;
+    for layer_index in 0..first.layers.len().max(second.layers.len()) {
+        let mut type_counts: HashMap<usize, i64> = HashMap::new();
+        if let Some(layer) = first.layers.get(layer_index) {
+            layer.nodes.iter().for_each(|node| *type_counts.entry(node.node_type_id).or_insert(0) += 1);
+        }
+        if let Some(layer) = second.layers.get(layer_index) {
+            layer.nodes.iter().for_each(|node| *type_counts.entry(node.node_type_id).or_insert(0) -= 1);
+        }
+        node_type_difference += type_counts.values().map(|count| count.unsigned_abs()).sum::<u64>();
+    }
+    let node_count = first.node_count().max(second.node_count()).max(1) as f64;
+
+    let first_keys = connection_keys(first);
+    let second_keys = connection_keys(second);
+    let mut connection_difference = 0;
+    for (key, count) in &first_keys {
+        connection_difference += count.abs_diff(*second_keys.get(key).unwrap_or(&0));
+    }
+    for (key, count) in &second_keys {
+        if !first_keys.contains_key(key) {
+            connection_difference += count;
+        }
+    }
+    let connection_count = first_keys.values().sum::<usize>().max(second_keys.values().sum::<usize>()).max(1) as f64;
+
+    let second_constants = constants(second);
+    let mut constant_difference = 0.0;
+    let mut matching_constants = 0;
+    for (position, value) in constants(first) {
+        if let Some(other_value) = second_constants.get(&position) {
+            constant_difference += (value - other_value).abs();
+            matching_constants += 1;
+        }
+    }
+    if matching_constants > 0 {
+        constant_difference /= matching_constants as f64;
+    }
+
+    coefficients.node_type * node_type_difference as f64 / node_count
+        + coefficients.connection * connection_difference as f64 / connection_count
+        + coefficients.constant * constant_difference
+}
+
+pub(crate) fn assign_species(species: &mut Vec<Species>, next_species_id: &mut usize, population: &[Network], losses: &[f64], settings: &SpeciationSettings, generation: usize) {
+    for existing_species in species.iter_mut() {
+        existing_species.members.clear();
+    }
+    for (network_index, network) in population.iter().enumerate() {
+        let matching_species = species.iter_mut().find(|existing_species| {
+            structural_distance(&existing_species.representative, network, &settings.coefficients) < settings.threshold
+        });
+        match matching_species {
+            Some(existing_species) => existing_species.members.push(network_index),
+            None => {
+                species.push(Species {
+                    id: *next_species_id,
+                    representative: network.clone(),
+                    members: vec![network_index],
+                    best_loss: f64::INFINITY,
+                    last_improved_generation: generation,
+                });
+                *next_species_id += 1;
+            }
+        }
+    }
+    species.retain(|existing_species| !existing_species.members.is_empty());
+
+    for existing_species in species.iter_mut() {
+        let best_member = *existing_species.members.iter().min_by(|first, second| losses[**first].total_cmp(&losses[**second])).unwrap();
+        if losses[best_member] < existing_species.best_loss {
+            existing_species.best_loss = losses[best_member];
+            existing_species.last_improved_generation = generation;
+        }
+        existing_species.representative = population[best_member].clone(); //The best member represents the species next generation
+    }
+} //Sorts the population into species, creating species for networks that don't fit any, and tracks their improvement
+
+pub(crate) fn shared_losses(species: &[Species], losses: &[f64]) -> Vec<f64> {
+    let mut shared = losses.to_vec();
+    for existing_species in species {
+        for member in &existing_species.members {
+            shared[*member] = losses[*member] * existing_species.members.len() as f64;
+        }
+    }
+    shared
+} //Fitness sharing for losses: each loss is multiplied by the size of its species, so crowded species are penalized
+
+pub(crate) fn breeding_pool(species: &[Species], losses: &[f64], settings: &SpeciationSettings, generation: usize) -> Vec<usize> {
+    //Stagnant species are left out, unless they hold the best network of the population or every species is stagnant
+    let best_loss = losses.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut pool = Vec::new();
+    for existing_species in species {
+        let stagnant = existing_species.stagnant_generations(generation) >= settings.stagnation_limit;
+        let holds_best = existing_species.members.iter().any(|member| losses[*member] == best_loss);
+        if !stagnant || holds_best {
+            pool.extend(existing_species.members.iter().copied());
+        }
+    }
+    if pool.is_empty() {
+        pool = (0..losses.len()).collect();
+    }
+    pool.sort_unstable();
+    pool
+} //Indices of the networks allowed to breed
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simple_network(node_type: usize, constant: f64) -> Network {
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(node_type, 0);
+        network.connect_input(0, 0, 0).unwrap();
+        network.connect_constant(0, 0, constant).unwrap();
+        network
+    }
+
+    #[test]
+    fn distance_between_networks() {
+        let coefficients = SpeciationSettings::default().coefficients;
+        let network = simple_network(201, 1.0);
+        assert_eq!(structural_distance(&network, &network, &coefficients), 0.0);
+        assert_eq!(structural_distance(&network, &simple_network(201, 3.5), &coefficients), 0.4 * 2.5);
+        assert_eq!(structural_distance(&network, &simple_network(203, 1.0), &coefficients), 2.0);
+
+        let mut larger = simple_network(201, 1.0);
+        let _ = larger.generate_node_to_layer(201, 0);
+        larger.connect_input(0, 1, 1).unwrap();
+        let distance = structural_distance(&network, &larger, &coefficients);
+        assert_eq!(distance, structural_distance(&larger, &network, &coefficients));
+        assert_eq!(distance, 0.5 + 1.0 / 3.0);
+    }
+
+    #[test]
+    fn species_assignment_and_stagnation() {
+        let settings = SpeciationSettings { stagnation_limit: 2, ..SpeciationSettings::default() };
+        let population = vec![simple_network(201, 1.0), simple_network(201, 1.5), simple_network(104, 1.0)];
+        let mut species = Vec::new();
+        let mut next_species_id = 0;
+
+        assign_species(&mut species, &mut next_species_id, &population, &[1.0, 2.0, 5.0], &settings, 0);
+        assert_eq!(species.len(), 2);
+        assert_eq!(species[0].members(), &[0, 1]);
+        assert_eq!(shared_losses(&species, &[1.0, 2.0, 5.0]), vec![2.0, 4.0, 5.0]);
+
+        assign_species(&mut species, &mut next_species_id, &population, &[1.0, 2.0, 5.0], &settings, 2);
+        assert_eq!(species[1].stagnant_generations(2), 2);
+        assert_eq!(breeding_pool(&species, &[1.0, 2.0, 5.0], &settings, 2), vec![0, 1]); //The second species is stagnant
+    }
+}
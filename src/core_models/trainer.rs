@@ -0,0 +1,1308 @@
+use std::cmp::Ordering;
+use crate::core_models::constant_optimization::Refinement;
+use crate::core_models::dannn::{Complexity, ConnectionType, Error, Network, NodeSelectionTable};
+use crate::core_models::map_elites::Archive;
+use crate::core_models::novelty::{self, NoveltySettings};
+use crate::core_models::pareto;
+use crate::core_models::random::Rng;
+use crate::core_models::serialization::{self, parse};
+use crate::core_models::speciation::{self, Species, SpeciationSettings};
+
+/*
+The trainer evolves a population of networks. Every generation each network is scored with a user supplied fitness
+function (a loss, lower is better, see the fitness module), then the next generation is bred from the current one
+with tournament selection and mutation. Networks are only built out of the node types enabled in the trainers
+NodeSelectionTable, and node types with a higher prevalence are picked more often.
+
+The multi-objective mode (NSGA-II) scores networks with a vector of objectives instead of a single loss, and keeps the
+networks that are the best trade off between them (the Pareto front) rather than a single best network.
+
+With speciation enabled, the population is grouped into species of structurally similar networks (see the
+speciation module), tournaments compare shared losses, and stagnant species stop breeding.
+
+The MAP-Elites mode keeps no population of its own, it breeds from and fills an Archive (see the map_elites module).
+
+The novelty mode selects on how different the behavior of each network is from the rest, optionally mixed with the
+loss (see the novelty module). The best network is still tracked by loss alone.
+
+With refinement enabled, the constants of the best few networks are tuned every generation before breeding, and the
+tuned constants are passed on to their offspring (Lamarckian evolution, see the constant_optimization module).
+
+Every random choice goes through the trainers seeded Rng, so the same settings and seed always produce the same run.
+
+Progress can be followed by passing TrainerHooks to train_with_hooks, for example a MetricsLog (see the metrics
+module) that writes one line of statistics per generation. Every mode has a _with_hooks version of its train function.
+The multi-objective mode treats the first objective as the loss of a network for the best network and the hooks, and
+the MAP-Elites mode keeps the children of its last generation as the population.
+
+A checkpoint saves everything that changes during a run (the population, the Rng state, the generation counter, the
+species, the novelty archive, the best network and the NodeSelectionTable) as text between generations. Loading it
+with the same settings continues the run exactly as if it had never stopped. The settings themselves are not saved,
+and the MAP-Elites archive is saved on its own, train_map_elites writes it next to every checkpoint with ".archive"
+appended to the path (see Archive::load to resume it). A checkpoint looks like:
+
+    generation 12
+    rng 8127403982151870871
+    next_species 4
+    node_type 201 1
+    best 0.25
+    layer
+    ...
+    end
+    network
+    layer
+    ...
+    end
+    species 2 0.25 9 0 5 17
+    layer
+    ...
+    end
+    novelty 1.5 3 0.25
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComplexityWeights { //Loss added per unit of each complexity metric when using penalized selection
+    pub node: f64,
+    pub connection: f64,
+    pub depth: f64,
+    pub effective_node: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    Fitness, //Only the loss is compared
+    Penalized(ComplexityWeights), //The loss plus a weighted sum of complexity metrics is compared
+    Lexicographic(f64), //Losses within the given tolerance of each other are tied (see rank_networks), ties are broken by effective node count, node count, then connection count
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointSettings {
+    pub path: String,
+    pub interval: usize, //A checkpoint is written every interval generations
+}
+
+#[derive(Clone, Debug)]
+pub struct TrainerSettings {
+    pub population_size: usize,
+    pub input_count: usize, //Amount of inputs the networks read from
+    pub output_count: usize, //Amount of nodes in the last layer of the networks
+    pub hidden_layer_count: usize, //Layers before the output layer in newly generated networks
+    pub max_nodes_per_layer: usize, //Upper limit of nodes in a hidden layer of a newly generated network
+    pub constant_range: f64, //New constants are drawn between -constant_range and constant_range
+    pub mutation_rate: f64, //Chance of mutating an offspring, and of mutating it again after each mutation
+    pub tournament_size: usize,
+    pub elitism: usize, //Amount of best networks copied unchanged into the next generation
+    pub selection: Selection,
+    pub speciation: Option<SpeciationSettings>, //Speciation is off when None
+    pub novelty: NoveltySettings, //Only used by the novelty mode
+    pub refinement: Option<Refinement>, //Lamarckian constant tuning is off when None
+    pub checkpoint: Option<CheckpointSettings>, //Only used by the train functions, the step functions never checkpoint. Checkpointing is off when None
+    pub seed: u64,
+}
+
+impl Default for TrainerSettings {
+    fn default() -> TrainerSettings {
+        TrainerSettings {
+            population_size: 100,
+            input_count: 1,
+            output_count: 1,
+            hidden_layer_count: 2,
+            max_nodes_per_layer: 4,
+            constant_range: 10.0,
+            mutation_rate: 0.8,
+            tournament_size: 3,
+            elitism: 1,
+            selection: Selection::Fitness,
+            speciation: None,
+            novelty: NoveltySettings::default(),
+            refinement: None,
+            checkpoint: None,
+            seed: 0,
+        }
+    }
+}
+
+pub trait TrainerHooks {
+    fn on_generation_start(&mut self, _trainer: &Trainer) -> Result<(), Error> {
+        Ok(())
+    } //Called before a generation is evaluated
+
+    fn on_evaluated(&mut self, _trainer: &Trainer) -> Result<(), Error> {
+        Ok(())
+    } //Called once the population is evaluated (and refined), before the next generation is bred from it
+
+    fn on_new_best(&mut self, _trainer: &Trainer, _network: &Network, _loss: f64) -> Result<(), Error> {
+        Ok(())
+    } //Called after on_evaluated when the generation found a network with a lower loss than any before
+
+    fn on_finish(&mut self, _trainer: &Trainer) -> Result<(), Error> {
+        Ok(())
+    } //Called once the last generation has been bred
+} //Every hook does nothing unless overridden, an error from a hook stops training
+
+impl TrainerHooks for () {}
+
+#[derive(Clone, Copy, Debug)]
+enum Mutation {
+    AddNode,
+    RemoveNode,
+    ReplaceNodeType,
+    Rewire,
+    PerturbConstant,
+    InsertLayer,
+    RemoveLayer,
+}
+
+const MUTATIONS: [Mutation; 7] = [
+    Mutation::AddNode,
+    Mutation::RemoveNode,
+    Mutation::ReplaceNodeType,
+    Mutation::Rewire,
+    Mutation::PerturbConstant,
+    Mutation::InsertLayer,
+    Mutation::RemoveLayer,
+];
+
+#[derive(Debug)]
+pub struct Trainer {
+    settings: TrainerSettings,
+    node_selection_table: NodeSelectionTable,
+    node_types: Vec<(usize, f32)>, //Enabled node types and their prevalence, sorted by id so sampling is reproducible
+    population: Vec<Network>,
+    losses: Vec<f64>, //Loss of each network in the population, empty until the population is evaluated
+    complexities: Vec<Complexity>,
+    objectives: Vec<Vec<f64>>, //Objective vectors of the population in multi-objective mode
+    species: Vec<Species>,
+    next_species_id: usize,
+    novelty_archive: Vec<Vec<f64>>, //Behaviors remembered by the novelty mode
+    rng: Rng,
+    generation: usize,
+    best: Option<(Network, f64)>,
+}
+
+impl Trainer {
+    pub fn new(settings: TrainerSettings, node_selection_table: NodeSelectionTable) -> Result<Trainer, Error> {
+        let node_types: Vec<(usize, f32)> = node_selection_table.descriptors().iter()
+            .map(|descriptor| (descriptor.id(), descriptor.prevalence()))
+            .filter(|(_, prevalence)| *prevalence > 0.0)
+            .collect();
+        if node_types.is_empty() || settings.population_size == 0 || settings.output_count == 0 {
+            return Err(Error::Initialize);
+        }
+
+        Ok(Trainer {
+            rng: Rng::new(settings.seed),
+            settings,
+            node_selection_table,
+            node_types,
+            population: Vec::new(),
+            losses: Vec::new(),
+            complexities: Vec::new(),
+            objectives: Vec::new(),
+            species: Vec::new(),
+            next_species_id: 0,
+            novelty_archive: Vec::new(),
+            generation: 0,
+            best: None,
+        })
+    }
+
+    pub fn settings(&self) -> &TrainerSettings {
+        &self.settings
+    }
+
+    pub fn node_selection_table(&self) -> &NodeSelectionTable {
+        &self.node_selection_table
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn population(&self) -> &[Network] {
+        &self.population
+    }
+
+    pub fn losses(&self) -> &[f64] {
+        &self.losses
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    } //Species of the last evaluated population, empty when speciation is off
+
+    pub fn best(&self) -> Option<(&Network, f64)> {
+        self.best.as_ref().map(|(network, loss)| (network, *loss))
+    } //Best network found so far and its loss
+
+    pub fn initialize_population(&mut self) {
+        self.population = (0..self.settings.population_size).map(|_| self.random_network()).collect();
+        self.losses.clear();
+        self.complexities.clear();
+        self.objectives.clear();
+    } //Replaces the population with newly generated networks
+
+    pub fn step<F>(&mut self, fitness: &mut F) where F: FnMut(&mut Network, &mut Rng) -> f64 {
+        let _ = self.step_with_hooks(fitness, &mut ()); //The empty hooks never fail
+    } //Runs one generation: evaluates the population and breeds the next one
+
+    fn step_with_hooks<F, H>(&mut self, fitness: &mut F, hooks: &mut H) -> Result<(), Error> where F: FnMut(&mut Network, &mut Rng) -> f64, H: TrainerHooks {
+        hooks.on_generation_start(self)?;
+        if self.population.is_empty() {
+            self.initialize_population();
+        }
+        let previous_best_loss = self.best.as_ref().map(|(_, loss)| *loss);
+        self.evaluate_population(fitness);
+        self.refine(fitness);
+        self.report_evaluated(hooks, previous_best_loss)?;
+        self.next_generation();
+        Ok(())
+    } //Same as step, calling the hooks along the way
+
+    fn report_evaluated<H>(&self, hooks: &mut H, previous_best_loss: Option<f64>) -> Result<(), Error> where H: TrainerHooks {
+        hooks.on_evaluated(self)?;
+        if let Some((network, loss)) = &self.best {
+            if previous_best_loss.is_none_or(|previous| *loss < previous) {
+                hooks.on_new_best(self, network, *loss)?;
+            }
+        }
+        Ok(())
+    } //Calls on_evaluated, then on_new_best if the generation beat the best loss from before it
+
+    pub fn refine<F>(&mut self, fitness: &mut F) where F: FnMut(&mut Network, &mut Rng) -> f64 {
+        let refinement = match &self.settings.refinement {
+            Some(refinement) => refinement.clone(),
+            None => return,
+        };
+        for network_index in self.ranking().into_iter().take(refinement.network_count) {
+            let loss = refinement.optimizer.optimize(&mut self.population[network_index], &mut self.rng, &mut *fitness);
+            if loss <= self.losses[network_index] { //A noisy fitness could make the tuned constants look worse
+                self.losses[network_index] = loss;
+            }
+            let improved = match &self.best {
+                Some((_, best_loss)) => loss < *best_loss,
+                None => true,
+            };
+            if improved {
+                self.best = Some((self.population[network_index].clone(), loss));
+            }
+        }
+    } //Tunes the constants of the best networks of the evaluated population in place
+
+    pub fn train<F>(&mut self, generations: usize, fitness: F) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> f64 {
+        self.train_with_hooks(generations, fitness, &mut ())
+    } //Runs a number of generations, returns the best network found, fails only if a checkpoint can't be written
+
+    pub fn train_with_hooks<F, H>(&mut self, generations: usize, mut fitness: F, hooks: &mut H) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> f64, H: TrainerHooks {
+        for _ in 0..generations {
+            self.step_with_hooks(&mut fitness, hooks)?;
+            self.checkpoint_if_due()?;
+        }
+        hooks.on_finish(self)?;
+        Ok(self.best.as_ref().map(|(network, _)| network.clone()))
+    } //Same as train, calling the hooks along the way
+
+    pub fn evaluate_population<F>(&mut self, fitness: &mut F) where F: FnMut(&mut Network, &mut Rng) -> f64 {
+        if self.population.is_empty() {
+            self.initialize_population();
+        }
+        self.losses.clear();
+        self.complexities.clear();
+        for network in self.population.iter_mut() {
+            let loss = fitness(network, &mut self.rng);
+            self.losses.push(if loss.is_nan() { f64::INFINITY } else { loss });
+            self.complexities.push(network.complexity());
+        }
+        if let Some(speciation_settings) = &self.settings.speciation {
+            speciation::assign_species(&mut self.species, &mut self.next_species_id, &self.population, &self.losses, speciation_settings, self.generation);
+        }
+        self.remember_best();
+    } //Scores every network in the population and remembers the best one
+
+    fn remember_best(&mut self) {
+        let best_index = match self.ranking().first() {
+            Some(best_index) => *best_index,
+            None => return,
+        };
+        let best_loss = self.losses[best_index];
+        let improved = match &self.best {
+            Some((_, loss)) => best_loss < *loss,
+            None => true,
+        };
+        if improved {
+            self.best = Some((self.population[best_index].clone(), best_loss));
+        }
+    } //Replaces the best network if the evaluated population holds a better one
+
+    pub fn next_generation(&mut self) {
+        if self.losses.len() != self.population.len() || self.population.is_empty() {
+            return; //Nothing to breed from until the population is evaluated
+        }
+        let (pool, selection_losses) = match &self.settings.speciation {
+            Some(speciation_settings) => (
+                speciation::breeding_pool(&self.species, &self.losses, speciation_settings, self.generation),
+                speciation::shared_losses(&self.species, &self.losses),
+            ),
+            None => ((0..self.population.len()).collect(), self.losses.clone()),
+        };
+        self.breed(&pool, &selection_losses);
+    } //Breeds the next generation from the evaluated population, does nothing if the population isn't evaluated
+
+    fn breed(&mut self, pool: &[usize], selection_losses: &[f64]) {
+        let ranking = self.ranking();
+        let mut next_population = Vec::new();
+        for network_index in ranking.iter().take(self.settings.elitism.min(self.settings.population_size)) {
+            next_population.push(self.population[*network_index].clone());
+        }
+        while next_population.len() < self.settings.population_size {
+            let parent_index = self.tournament(pool, selection_losses);
+            next_population.push(self.offspring(parent_index));
+        }
+
+        self.population = next_population;
+        self.losses.clear();
+        self.complexities.clear();
+        self.generation += 1;
+    } //Keeps the elites by loss, fills the rest of the next generation with tournaments over the pool using the selection losses
+
+    pub fn novelty_archive(&self) -> &[Vec<f64>] {
+        &self.novelty_archive
+    }
+
+    pub fn step_novelty<F>(&mut self, evaluate: &mut F) where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>) {
+        let _ = self.step_novelty_with_hooks(evaluate, &mut ()); //The empty hooks never fail
+    } //Runs one generation selecting on novelty mixed with loss, the evaluate function returns the loss and behavior of a network
+
+    fn step_novelty_with_hooks<F, H>(&mut self, evaluate: &mut F, hooks: &mut H) -> Result<(), Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>), H: TrainerHooks {
+        hooks.on_generation_start(self)?;
+        if self.population.is_empty() {
+            self.initialize_population();
+        }
+        let previous_best_loss = self.best.as_ref().map(|(_, loss)| *loss);
+        let mut behaviors = Vec::new();
+        self.evaluate_population(&mut |network: &mut Network, rng: &mut Rng| {
+            let (loss, behavior) = evaluate(network, rng);
+            behaviors.push(behavior);
+            loss
+        });
+        self.report_evaluated(hooks, previous_best_loss)?;
+
+        let novelty_settings = self.settings.novelty.clone();
+        let scores = novelty::novelty_scores(&behaviors, &self.novelty_archive, novelty_settings.nearest_neighbors);
+        novelty::update_archive(&mut self.novelty_archive, &behaviors, &scores, &novelty_settings);
+        let selection_losses = novelty::hybrid_losses(&self.losses, &scores, novelty_settings.novelty_weight);
+        let pool: Vec<usize> = (0..self.population.len()).collect();
+        self.breed(&pool, &selection_losses);
+        Ok(())
+    } //Same as step_novelty, calling the hooks along the way
+
+    pub fn train_novelty<F>(&mut self, generations: usize, evaluate: F) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>) {
+        self.train_novelty_with_hooks(generations, evaluate, &mut ())
+    } //Runs a number of novelty generations, returns the network with the lowest loss found
+
+    pub fn train_novelty_with_hooks<F, H>(&mut self, generations: usize, mut evaluate: F, hooks: &mut H) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>), H: TrainerHooks {
+        for _ in 0..generations {
+            self.step_novelty_with_hooks(&mut evaluate, hooks)?;
+            self.checkpoint_if_due()?;
+        }
+        hooks.on_finish(self)?;
+        Ok(self.best.as_ref().map(|(network, _)| network.clone()))
+    } //Same as train_novelty, calling the hooks along the way
+
+    fn offspring(&mut self, parent_index: usize) -> Network {
+        let mut offspring = self.population[parent_index].clone();
+        while self.rng.next_f64() < self.settings.mutation_rate {
+            self.mutate(&mut offspring);
+        }
+        offspring
+    } //Copies a parent and mutates the copy
+
+    pub fn objectives(&self) -> &[Vec<f64>] {
+        &self.objectives
+    }
+
+    pub fn step_multi_objective<F>(&mut self, objectives: &mut F) -> Result<(), Error> where F: FnMut(&mut Network, &mut Rng) -> Vec<f64> {
+        self.step_multi_objective_with_hooks(objectives, &mut ())
+    } //Runs one NSGA-II generation, the population stays evaluated between generations. Fails if the objective vectors differ in length
+
+    fn step_multi_objective_with_hooks<F, H>(&mut self, objectives: &mut F, hooks: &mut H) -> Result<(), Error> where F: FnMut(&mut Network, &mut Rng) -> Vec<f64>, H: TrainerHooks {
+        hooks.on_generation_start(self)?;
+        if self.population.is_empty() {
+            self.initialize_population();
+        }
+        let previous_best_loss = self.best.as_ref().map(|(_, loss)| *loss);
+        if self.objectives.len() != self.population.len() {
+            self.objectives.clear();
+            self.objectives = self.evaluate_objectives(&mut self.population.clone(), objectives)?;
+        }
+        self.losses = self.objectives.iter().map(|values| values.first().copied().unwrap_or(f64::INFINITY)).collect();
+        self.complexities = self.population.iter().map(Network::complexity).collect();
+        self.remember_best();
+        self.report_evaluated(hooks, previous_best_loss)?;
+
+        //Offspring are bred with crowded tournaments: lower front rank wins, then larger crowding distance
+        let (ranks, distances) = pareto::rank_and_crowding(&self.objectives);
+        let mut offspring = Vec::new();
+        while offspring.len() < self.settings.population_size {
+            let mut winner = self.rng.range(0, self.population.len());
+            for _ in 1..self.settings.tournament_size {
+                let challenger = self.rng.range(0, self.population.len());
+                if ranks[challenger] < ranks[winner] || (ranks[challenger] == ranks[winner] && distances[challenger] > distances[winner]) {
+                    winner = challenger;
+                }
+            }
+            offspring.push(self.offspring(winner));
+        }
+        let offspring_objectives = self.evaluate_objectives(&mut offspring, objectives)?;
+
+        //Parents and offspring compete together, whole fronts are kept until one no longer fits, which is cut by crowding distance
+        let mut combined_population = std::mem::take(&mut self.population);
+        combined_population.append(&mut offspring);
+        let mut combined_objectives = std::mem::take(&mut self.objectives);
+        combined_objectives.extend(offspring_objectives);
+
+        let mut survivors = Vec::new();
+        for front in pareto::non_dominated_sort(&combined_objectives) {
+            if survivors.len() + front.len() <= self.settings.population_size {
+                survivors.extend(front);
+                continue;
+            }
+            let distances = pareto::crowding_distance(&combined_objectives, &front);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.sort_by(|first, second| distances[*second].total_cmp(&distances[*first]));
+            for position in order.into_iter().take(self.settings.population_size - survivors.len()) {
+                survivors.push(front[position]);
+            }
+            break;
+        }
+
+        self.population = survivors.iter().map(|index| combined_population[*index].clone()).collect();
+        self.objectives = survivors.iter().map(|index| combined_objectives[*index].clone()).collect();
+        self.losses.clear();
+        self.complexities.clear();
+        self.generation += 1;
+        Ok(())
+    } //Same as step_multi_objective, calling the hooks along the way
+
+    pub fn train_multi_objective<F>(&mut self, generations: usize, objectives: F) -> Result<Vec<(Network, Vec<f64>)>, Error> where F: FnMut(&mut Network, &mut Rng) -> Vec<f64> {
+        self.train_multi_objective_with_hooks(generations, objectives, &mut ())
+    } //Runs a number of NSGA-II generations, returns the Pareto front, fails if a checkpoint can't be written or the objective vectors differ in length
+
+    pub fn train_multi_objective_with_hooks<F, H>(&mut self, generations: usize, mut objectives: F, hooks: &mut H) -> Result<Vec<(Network, Vec<f64>)>, Error> where F: FnMut(&mut Network, &mut Rng) -> Vec<f64>, H: TrainerHooks {
+        for _ in 0..generations {
+            self.step_multi_objective_with_hooks(&mut objectives, hooks)?;
+            self.checkpoint_if_due()?;
+        }
+        hooks.on_finish(self)?;
+        Ok(self.pareto_front())
+    } //Same as train_multi_objective, calling the hooks along the way
+
+    pub fn pareto_front(&self) -> Vec<(Network, Vec<f64>)> {
+        match pareto::non_dominated_sort(&self.objectives).first() {
+            Some(front) => front.iter().map(|index| (self.population[*index].clone(), self.objectives[*index].clone())).collect(),
+            None => Vec::new(),
+        }
+    } //Networks of the current population that no other network dominates, with their objective vectors
+
+    pub fn step_map_elites<F>(&mut self, archive: &mut Archive, evaluate: &mut F) -> usize where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>) {
+        self.step_map_elites_with_hooks(archive, evaluate, &mut ()).unwrap_or(0) //The empty hooks never fail
+    } //Breeds population_size children from random elites of the archive (random networks while it is empty), returns how many were stored
+
+    fn step_map_elites_with_hooks<F, H>(&mut self, archive: &mut Archive, evaluate: &mut F, hooks: &mut H) -> Result<usize, Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>), H: TrainerHooks {
+        hooks.on_generation_start(self)?;
+        let previous_best_loss = self.best.as_ref().map(|(_, loss)| *loss);
+        self.population.clear();
+        self.losses.clear();
+        let mut inserted = 0;
+        for _ in 0..self.settings.population_size {
+            let mut candidate = if archive.is_empty() {
+                self.random_network()
+            } else {
+                let elites = archive.elites();
+                let mut child = elites[self.rng.range(0, elites.len())].1.network.clone();
+                self.mutate(&mut child); //Every child is mutated at least once, a copy could never improve its cell
+                while self.rng.next_f64() < self.settings.mutation_rate {
+                    self.mutate(&mut child);
+                }
+                child
+            };
+            let (loss, behavior) = evaluate(&mut candidate, &mut self.rng);
+            let loss = if loss.is_nan() { f64::INFINITY } else { loss };
+            self.population.push(candidate.clone());
+            self.losses.push(loss);
+            if archive.insert(candidate, loss, behavior) {
+                inserted += 1;
+            }
+        }
+        self.complexities = self.population.iter().map(Network::complexity).collect();
+        self.remember_best();
+        self.report_evaluated(hooks, previous_best_loss)?;
+        self.generation += 1;
+        Ok(inserted)
+    } //Same as step_map_elites, calling the hooks along the way. The children of the generation are kept as the population so hooks can measure them
+
+    pub fn train_map_elites<F>(&mut self, generations: usize, archive: &mut Archive, evaluate: F) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>) {
+        self.train_map_elites_with_hooks(generations, archive, evaluate, &mut ())
+    } //Runs a number of MAP-Elites generations, returns the elite with the lowest loss
+
+    pub fn train_map_elites_with_hooks<F, H>(&mut self, generations: usize, archive: &mut Archive, mut evaluate: F, hooks: &mut H) -> Result<Option<Network>, Error> where F: FnMut(&mut Network, &mut Rng) -> (f64, Vec<f64>), H: TrainerHooks {
+        for _ in 0..generations {
+            self.step_map_elites_with_hooks(archive, &mut evaluate, hooks)?;
+            if self.checkpoint_if_due()? {
+                if let Some(checkpoint) = &self.settings.checkpoint {
+                    archive.save(&format!("{}.archive", checkpoint.path))?;
+                }
+            }
+        }
+        hooks.on_finish(self)?;
+        Ok(archive.best().map(|elite| elite.network.clone()))
+    } //Same as train_map_elites, calling the hooks along the way
+
+    fn evaluate_objectives<F>(&mut self, networks: &mut [Network], objectives: &mut F) -> Result<Vec<Vec<f64>>, Error> where F: FnMut(&mut Network, &mut Rng) -> Vec<f64> {
+        let mut objective_count = self.objectives.first().map(Vec::len);
+        let mut evaluated = Vec::new();
+        for network in networks.iter_mut() {
+            let values: Vec<f64> = objectives(network, &mut self.rng).into_iter().map(|objective| if objective.is_nan() { f64::INFINITY } else { objective }).collect();
+            if *objective_count.get_or_insert(values.len()) != values.len() {
+                return Err(Error::ObjectiveCount);
+            }
+            evaluated.push(values);
+        }
+        Ok(evaluated)
+    } //Every objective vector must be as long as the first one, the Pareto ranking compares them element by element
+
+    fn ranking(&self) -> Vec<usize> {
+        rank_networks(&self.settings.selection, &self.losses, &self.complexities)
+    } //Indices of the evaluated population, best first
+
+    fn tournament(&mut self, pool: &[usize], losses: &[f64]) -> usize {
+        let mut winner = pool[self.rng.range(0, pool.len())];
+        for _ in 1..self.settings.tournament_size {
+            let challenger = pool[self.rng.range(0, pool.len())];
+            if compare_networks(&self.settings.selection, losses[challenger], &self.complexities[challenger], losses[winner], &self.complexities[winner]) == Ordering::Less {
+                winner = challenger;
+            }
+        }
+        winner
+    } //Picks the best of a few random networks from the pool, compared by the given losses
+
+    pub fn random_network(&mut self) -> Network {
+        let mut network = Network::new();
+        network.node_selection_table = self.node_selection_table.clone();
+        for layer_index in 0..=self.settings.hidden_layer_count {
+            let _ = network.generate_layer_to_network();
+            let node_count = if layer_index == self.settings.hidden_layer_count {
+                self.settings.output_count
+            } else {
+                self.rng.range(1, self.settings.max_nodes_per_layer.max(1) + 1)
+            };
+            for _ in 0..node_count {
+                let node_type = self.random_node_type();
+                let _ = network.generate_node_to_layer(node_type, layer_index);
+                let node_index = network.layers[layer_index].nodes.len() - 1;
+                self.fill_connections(&mut network, layer_index, node_index);
+            }
+        }
+        network
+    } //Generates a network from the enabled node types
+
+    fn random_node_type(&mut self) -> usize {
+        let total: f64 = self.node_types.iter().map(|(_, prevalence)| *prevalence as f64).sum();
+        let mut choice = self.rng.next_f64() * total;
+        for (id, prevalence) in &self.node_types {
+            if choice < *prevalence as f64 {
+                return *id;
+            }
+            choice -= *prevalence as f64;
+        }
+        self.node_types.last().unwrap().0
+    } //Picks an enabled node type, weighted by prevalence
+
+    fn fill_connections(&mut self, network: &mut Network, layer_index: usize, node_index: usize) {
+        let node_type = network.layers[layer_index].nodes[node_index].node_type_id;
+        let (minimum_connections, maximum_connections) = match NodeSelectionTable::template_descriptor(node_type) {
+            Some(descriptor) => (descriptor.minimum_connections.unwrap_or(0), descriptor.maximum_connections),
+            None => (1, None),
+        };
+        let maximum_connections = maximum_connections.unwrap_or(minimum_connections + 2); //Variadic nodes get a couple of extra inputs at most
+        let target_connections = self.rng.range(minimum_connections, maximum_connections + 1);
+        while network.layers[layer_index].nodes[node_index].connections.len() < target_connections {
+            self.add_random_connection(network, layer_index, node_index);
+        }
+    } //Gives a node a random amount of random connections within the arity of its type
+
+    fn add_random_connection(&mut self, network: &mut Network, layer_index: usize, node_index: usize) {
+        let earlier_nodes: usize = network.layers[..layer_index].iter().map(|layer| layer.nodes.len()).sum();
+        let choice = self.rng.next_f64();
+        if earlier_nodes > 0 && choice < 0.5 {
+            let mut source = self.rng.range(0, earlier_nodes);
+            for (source_layer, layer) in network.layers[..layer_index].iter().enumerate() {
+                if source < layer.nodes.len() {
+                    let _ = network.connect_node(layer_index, node_index, source_layer, source);
+                    return;
+                }
+                source -= layer.nodes.len();
+            }
+        } else if self.settings.input_count > 0 && choice < 0.85 {
+            let input_id = self.rng.range(0, self.settings.input_count);
+            let _ = network.connect_input(layer_index, node_index, input_id);
+        } else {
+            let value = (self.rng.next_f64() * 2.0 - 1.0) * self.settings.constant_range;
+            let _ = network.connect_constant(layer_index, node_index, value);
+        }
+    } //Connects a node to a random earlier node, input, or new constant
+
+    fn rewire(&mut self, network: &mut Network, layer_index: usize, node_index: usize) {
+        let connection_count = network.layers[layer_index].nodes[node_index].connections.len();
+        if connection_count == 0 {
+            return;
+        }
+        let connection_index = self.rng.range(0, connection_count);
+        self.add_random_connection(network, layer_index, node_index);
+        let connections = &mut network.layers[layer_index].nodes[node_index].connections;
+        if connections.len() > connection_count {
+            let new_connection = connections.pop().unwrap();
+            connections[connection_index] = new_connection;
+        }
+    } //Replaces one connection of a node in place, so the other inputs keep their positions for non-commutative nodes
+
+    fn repair(&mut self, network: &mut Network, under_minimum: Vec<(usize, usize)>) {
+        for (layer_index, node_index) in under_minimum {
+            self.fill_connections(network, layer_index, node_index);
+        }
+    } //Gives nodes that lost connections during a mutation enough connections again
+
+    pub fn mutate(&mut self, network: &mut Network) {
+        let output_layer = network.layers.len().saturating_sub(1);
+        let mutation = MUTATIONS[self.rng.range(0, MUTATIONS.len())];
+        match mutation {
+            Mutation::AddNode if output_layer > 0 => {
+                let layer_index = self.rng.range(0, output_layer);
+                let node_type = self.random_node_type();
+                let _ = network.generate_node_to_layer(node_type, layer_index);
+                let node_index = network.layers[layer_index].nodes.len() - 1;
+                self.fill_connections(network, layer_index, node_index);
+            }
+            Mutation::RemoveNode if output_layer > 0 => {
+                let layer_index = self.rng.range(0, output_layer);
+                if network.layers[layer_index].nodes.len() > 1 { //Empty hidden layers are only removed by RemoveLayer
+                    let node_index = self.rng.range(0, network.layers[layer_index].nodes.len());
+                    if let Ok(under_minimum) = network.remove_node(layer_index, node_index) {
+                        self.repair(network, under_minimum);
+                    }
+                }
+            }
+            Mutation::ReplaceNodeType => {
+                if let Some((layer_index, node_index)) = self.random_node(network) {
+                    let node_type = self.random_node_type();
+                    if let Ok(under_minimum) = network.replace_node_type(layer_index, node_index, node_type) {
+                        self.repair(network, under_minimum);
+                    }
+                }
+            }
+            Mutation::Rewire => {
+                if let Some((layer_index, node_index)) = self.random_node(network) {
+                    self.rewire(network, layer_index, node_index);
+                }
+            }
+            Mutation::PerturbConstant => {
+                let mut constants = Vec::new();
+                for (layer_index, layer) in network.layers.iter().enumerate() {
+                    for (node_index, node) in layer.nodes.iter().enumerate() {
+                        for (connection_index, connection) in node.connections.iter().enumerate() {
+                            if let ConnectionType::Constant(_) = connection {
+                                constants.push((layer_index, node_index, connection_index));
+                            }
+                        }
+                    }
+                }
+                if !constants.is_empty() {
+                    let (layer_index, node_index, connection_index) = constants[self.rng.range(0, constants.len())];
+                    let change = (self.rng.next_f64() * 2.0 - 1.0) * self.settings.constant_range * 0.1;
+                    if let ConnectionType::Constant(constant) = &mut network.layers[layer_index].nodes[node_index].connections[connection_index] {
+                        constant.value += change;
+                    }
+                }
+            }
+            Mutation::InsertLayer if output_layer > 0 => {
+                let layer_index = self.rng.range(1, output_layer + 1);
+                if network.insert_layer_at(layer_index).is_ok() {
+                    let node_type = self.random_node_type();
+                    let _ = network.generate_node_to_layer(node_type, layer_index);
+                    self.fill_connections(network, layer_index, 0);
+                }
+            }
+            Mutation::RemoveLayer if output_layer > 1 => {
+                let layer_index = self.rng.range(0, output_layer);
+                if let Ok(under_minimum) = network.remove_layer(layer_index) {
+                    self.repair(network, under_minimum);
+                }
+            }
+            _ => {}
+        }
+    } //Applies one random mutation to a network, the output layer always keeps its amount of nodes
+
+    fn random_node(&mut self, network: &Network) -> Option<(usize, usize)> {
+        let node_count = network.node_count();
+        if node_count == 0 {
+            return None;
+        }
+        let mut node = self.rng.range(0, node_count);
+        for (layer_index, layer) in network.layers.iter().enumerate() {
+            if node < layer.nodes.len() {
+                return Some((layer_index, node));
+            }
+            node -= layer.nodes.len();
+        }
+        None
+    }
+    pub fn checkpoint_if_due(&self) -> Result<bool, Error> {
+        match &self.settings.checkpoint {
+            Some(checkpoint) if checkpoint.interval > 0 && self.generation.is_multiple_of(checkpoint.interval) => {
+                self.save_checkpoint(&checkpoint.path)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    } //Writes a checkpoint if the checkpoint settings ask for one this generation, returns whether it did
+
+    pub fn to_checkpoint_text(&self) -> String {
+        let mut text = String::new();
+        text.push_str(&format!("generation {}\n", self.generation));
+        text.push_str(&format!("rng {}\n", self.rng.state()));
+        text.push_str(&format!("next_species {}\n", self.next_species_id));
+        for descriptor in self.node_selection_table.descriptors() {
+            text.push_str(&format!("node_type {} {}\n", descriptor.id(), descriptor.prevalence()));
+        }
+        if let Some((network, loss)) = &self.best {
+            text.push_str(&format!("best {}\n", loss));
+            text.push_str(&serialization::network_to_text(network));
+            text.push_str("end\n");
+        }
+        for network in &self.population {
+            text.push_str("network\n");
+            text.push_str(&serialization::network_to_text(network));
+            text.push_str("end\n");
+        }
+        if !self.losses.is_empty() {
+            text.push_str(&format!("losses {}\n", join(&self.losses)));
+        }
+        for objectives in &self.objectives {
+            text.push_str(&format!("objectives {}\n", join(objectives)));
+        }
+        for species in &self.species {
+            text.push_str(&format!("species {} {} {} {}\n", species.id, species.best_loss, species.last_improved_generation, join(&species.members)));
+            text.push_str(&serialization::network_to_text(&species.representative));
+            text.push_str("end\n");
+        }
+        for behavior in &self.novelty_archive {
+            text.push_str(&format!("novelty {}\n", join(behavior)));
+        }
+        text
+    }
+
+    pub fn from_checkpoint_text(settings: TrainerSettings, text: &str) -> Result<Trainer, Error> {
+        let mut node_selection_table = NodeSelectionTable::new();
+        let mut generation = 0;
+        let mut rng_state = settings.seed;
+        let mut next_species_id = 0;
+        let mut best = None;
+        let mut population = Vec::new();
+        let mut losses = Vec::new();
+        let mut objectives = Vec::new();
+        let mut species = Vec::new();
+        let mut novelty_archive = Vec::new();
+
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["generation", value] => generation = parse(value)?,
+                ["rng", value] => rng_state = parse(value)?,
+                ["next_species", value] => next_species_id = parse(value)?,
+                ["node_type", id, prevalence] => {
+                    let id = parse(id)?;
+                    if NodeSelectionTable::template_descriptor(id).is_none() {
+                        return Err(Error::ModelParse);
+                    }
+                    node_selection_table.enable_by_id(id);
+                    node_selection_table.set_prevalence_by_id(id, parse(prevalence)?)?;
+                }
+                ["best", loss] => {
+                    let loss = parse(loss)?;
+                    best = Some((serialization::read_network_block(&mut lines)?, loss));
+                }
+                ["network"] => population.push(serialization::read_network_block(&mut lines)?),
+                ["losses", values @ ..] => losses = parse_all(values)?,
+                ["objectives", values @ ..] => objectives.push(parse_all(values)?),
+                ["species", id, best_loss, last_improved_generation, members @ ..] => species.push(Species {
+                    id: parse(id)?,
+                    best_loss: parse(best_loss)?,
+                    last_improved_generation: parse(last_improved_generation)?,
+                    members: parse_all(members)?,
+                    representative: serialization::read_network_block(&mut lines)?,
+                }),
+                ["novelty", values @ ..] => novelty_archive.push(parse_all(values)?),
+                _ => return Err(Error::ModelParse),
+            }
+        }
+        if (!losses.is_empty() && losses.len() != population.len()) || (!objectives.is_empty() && objectives.len() != population.len()) {
+            return Err(Error::ModelParse);
+        }
+
+        let mut trainer = Trainer::new(settings, node_selection_table)?;
+        trainer.complexities = if losses.is_empty() { Vec::new() } else { population.iter().map(Network::complexity).collect() };
+        trainer.population = population;
+        trainer.losses = losses;
+        trainer.objectives = objectives;
+        trainer.species = species;
+        trainer.next_species_id = next_species_id;
+        trainer.novelty_archive = novelty_archive;
+        trainer.rng = Rng::new(rng_state);
+        trainer.generation = generation;
+        trainer.best = best;
+        Ok(trainer)
+    } //Continues a run from a checkpoint, the settings should be the ones the run was started with
+
+    pub fn save_checkpoint(&self, path: &str) -> Result<(), Error> {
+        serialization::write_atomically(path, &self.to_checkpoint_text())
+    } //A crash while saving never leaves a half written checkpoint
+
+    pub fn load_checkpoint(settings: TrainerSettings, path: &str) -> Result<Trainer, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::FileRead)?;
+        Trainer::from_checkpoint_text(settings, &text)
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(ToString::to_string).collect::<Vec<String>>().join(" ")
+}
+
+fn parse_all<T: std::str::FromStr>(words: &[&str]) -> Result<Vec<T>, Error> {
+    words.iter().map(|word| parse(word)).collect()
+}
+
+pub fn compare_networks(selection: &Selection, first_loss: f64, first: &Complexity, second_loss: f64, second: &Complexity) -> Ordering {
+    match selection {
+        Selection::Fitness => first_loss.total_cmp(&second_loss),
+        Selection::Penalized(weights) => {
+            let penalty = |complexity: &Complexity| {
+                weights.node * complexity.node_count as f64
+                    + weights.connection * complexity.connection_count as f64
+                    + weights.depth * complexity.depth as f64
+                    + weights.effective_node * complexity.effective_node_count as f64
+            };
+            (first_loss + penalty(first)).total_cmp(&(second_loss + penalty(second)))
+        }
+        Selection::Lexicographic(tolerance) => {
+            if (first_loss - second_loss).abs() > *tolerance || first_loss.is_infinite() || second_loss.is_infinite() {
+                return first_loss.total_cmp(&second_loss);
+            }
+            first.effective_node_count.cmp(&second.effective_node_count)
+                .then(first.node_count.cmp(&second.node_count))
+                .then(first.connection_count.cmp(&second.connection_count))
+        }
+    }
+} //Orders two networks by loss and complexity under a selection mode, better first. Lexicographic ties are not transitive, so sort with rank_networks
+
+pub fn rank_networks(selection: &Selection, losses: &[f64], complexities: &[Complexity]) -> Vec<usize> {
+    /*
+    Sorting with compare_networks would panic (or give a wrong order) for lexicographic selection: 1.0 ties with 1.05
+    and 1.05 with 1.1, but 1.0 beats 1.1. Instead the networks are sorted by loss, split into runs where every loss is
+    within the tolerance of the first (best) loss of its run, and only each run is sorted by complexity.
+     */
+    let mut ranking: Vec<usize> = (0..losses.len()).collect();
+    let tolerance = match selection {
+        Selection::Lexicographic(tolerance) => *tolerance,
+        _ => {
+            ranking.sort_by(|first, second| compare_networks(selection, losses[*first], &complexities[*first], losses[*second], &complexities[*second]));
+            return ranking;
+        }
+    };
+    ranking.sort_by(|first, second| losses[*first].total_cmp(&losses[*second]));
+    let mut run_start = 0;
+    while run_start < ranking.len() {
+        let best_loss = losses[ranking[run_start]];
+        let mut run_end = run_start + 1;
+        while run_end < ranking.len() && best_loss.is_finite() && losses[ranking[run_end]] - best_loss <= tolerance {
+            run_end += 1;
+        }
+        ranking[run_start..run_end].sort_by(|first, second| {
+            let (first, second) = (&complexities[*first], &complexities[*second]);
+            first.effective_node_count.cmp(&second.effective_node_count)
+                .then(first.node_count.cmp(&second.node_count))
+                .then(first.connection_count.cmp(&second.connection_count))
+        }); //Stable, so equally complex networks stay in loss order
+        run_start = run_end;
+    }
+    ranking
+} //Indices of the networks, best first
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_models::dataset::Dataset;
+    use crate::core_models::fitness::{Fitness, Metric};
+
+    fn arithmetic_trainer(selection: Selection, seed: u64) -> Trainer {
+        let mut table = NodeSelectionTable::new();
+        table.enable_simple_arithmetic();
+        let settings = TrainerSettings {
+            population_size: 30,
+            input_count: 2,
+            selection,
+            seed,
+            ..TrainerSettings::default()
+        };
+        Trainer::new(settings, table).unwrap()
+    }
+
+    #[test]
+    fn trainer_requires_enabled_node_types() {
+        assert!(Trainer::new(TrainerSettings::default(), NodeSelectionTable::new()).is_err());
+    }
+
+    #[test]
+    fn public_steps_work_on_a_new_trainer() {
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 4);
+        trainer.next_generation();
+        assert_eq!(trainer.generation(), 0);
+        trainer.evaluate_population(&mut |network: &mut Network, _: &mut Rng| network.node_count() as f64);
+        assert_eq!(trainer.losses().len(), 30);
+        trainer.next_generation();
+        assert_eq!(trainer.generation(), 1);
+        trainer.next_generation();
+        assert_eq!(trainer.generation(), 1);
+    }
+
+    #[test]
+    fn generated_networks_respect_arity() {
+        let mut table = NodeSelectionTable::new();
+        table.enable_all();
+        let settings = TrainerSettings {
+            input_count: 3,
+            output_count: 2,
+            ..TrainerSettings::default()
+        };
+        let mut trainer = Trainer::new(settings, table).unwrap();
+        for _ in 0..20 {
+            let mut network = trainer.random_network();
+            for _ in 0..10 {
+                trainer.mutate(&mut network);
+            }
+            assert_eq!(network.layers.last().unwrap().nodes.len(), 2);
+            for layer in &network.layers {
+                for node in &layer.nodes {
+                    let descriptor = NodeSelectionTable::template_descriptor(node.node_type_id).unwrap();
+                    assert!(node.connections.len() >= descriptor.minimum_connections.unwrap_or(0));
+                    assert!(node.connections.len() <= descriptor.maximum_connections.unwrap_or(usize::MAX));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn training_is_reproducible_and_improves() {
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let run = |seed| {
+            let mut trainer = arithmetic_trainer(Selection::Fitness, seed);
+            trainer.train(15, |network, rng| fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY)).unwrap();
+            (trainer.best().unwrap().1, trainer.generation())
+        };
+        let (first_loss, generation) = run(3);
+        assert_eq!(generation, 15);
+        assert_eq!(first_loss, run(3).0);
+        assert!(first_loss < 10.0);
+    }
+
+    #[test]
+    fn multi_objective_training_returns_pareto_front() {
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 5);
+        let front = trainer.train_multi_objective(10, |network, rng| {
+            vec![fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY), network.node_count() as f64]
+        }).unwrap();
+        assert!(!front.is_empty());
+        assert_eq!(trainer.population().len(), 30);
+        assert_eq!(trainer.objectives().len(), 30);
+        for (_, first) in &front {
+            for (_, second) in &front {
+                assert!(!pareto::dominates(first, second));
+            }
+        }
+        for (network, objectives) in &front {
+            assert_eq!(network.node_count() as f64, objectives[1]);
+        }
+    }
+
+    #[test]
+    fn objective_vectors_of_different_lengths_fail() {
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 5);
+        let result = trainer.train_multi_objective(3, |network, _| vec![0.0; 1 + network.node_count() % 2]);
+        assert!(matches!(result, Err(Error::ObjectiveCount)));
+    }
+
+    #[test]
+    fn speciated_training_tracks_species() {
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 9);
+        trainer.settings.speciation = Some(SpeciationSettings { stagnation_limit: 3, ..SpeciationSettings::default() });
+        let mut score = |network: &mut Network, rng: &mut Rng| fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY);
+        for _ in 0..8 {
+            trainer.step(&mut score);
+        }
+        trainer.evaluate_population(&mut score);
+
+        assert!(trainer.species().len() > 1);
+        let mut members: Vec<usize> = trainer.species().iter().flat_map(|species| species.members().to_vec()).collect();
+        members.sort_unstable();
+        assert_eq!(members, (0..30).collect::<Vec<usize>>()); //Every network is in exactly one species
+        for species in trainer.species() {
+            assert!(species.stagnant_generations(trainer.generation()) <= trainer.generation());
+            assert!(species.best_loss() <= species.members().iter().map(|member| trainer.losses()[*member]).fold(f64::INFINITY, f64::min));
+        }
+    }
+
+    #[test]
+    fn checkpoint_resumes_identically() {
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut score = |network: &mut Network, rng: &mut Rng| fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY);
+        let speciated_trainer = || {
+            let mut table = NodeSelectionTable::new();
+            table.enable_simple_arithmetic();
+            table.set_prevalence_by_id(203, 0.25).unwrap();
+            let settings = TrainerSettings {
+                population_size: 30,
+                input_count: 2,
+                speciation: Some(SpeciationSettings::default()),
+                seed: 8,
+                ..TrainerSettings::default()
+            };
+            Trainer::new(settings, table).unwrap()
+        };
+
+        let mut uninterrupted = speciated_trainer();
+        for _ in 0..6 {
+            uninterrupted.step(&mut score);
+        }
+
+        let mut interrupted = speciated_trainer();
+        for _ in 0..3 {
+            interrupted.step(&mut score);
+        }
+        let checkpoint = interrupted.to_checkpoint_text();
+        let mut resumed = Trainer::from_checkpoint_text(interrupted.settings().clone(), &checkpoint).unwrap();
+        assert_eq!(resumed.to_checkpoint_text(), checkpoint);
+        assert_eq!(resumed.node_selection_table().get_prevalence_by_id(203).unwrap(), 0.25);
+        for _ in 0..3 {
+            resumed.step(&mut score);
+        }
+
+        assert_eq!(resumed.generation(), 6);
+        assert!(!resumed.species().is_empty());
+        assert_eq!(resumed.to_checkpoint_text(), uninterrupted.to_checkpoint_text());
+        assert!(Trainer::from_checkpoint_text(TrainerSettings::default(), "node_type 999 1\n").is_err());
+        assert!(Trainer::from_checkpoint_text(TrainerSettings::default(), "node_type 201 1\nnetwork\nlayer\n").is_err()); //Missing end
+    }
+
+    #[test]
+    fn periodic_checkpoints_are_written() {
+        let path = std::env::temp_dir().join(format!("steel_ml_checkpoint_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 1);
+        trainer.settings.checkpoint = Some(CheckpointSettings { path: path.clone(), interval: 2 });
+        trainer.train(3, |network, _| network.node_count() as f64).unwrap();
+
+        let resumed = Trainer::load_checkpoint(trainer.settings().clone(), &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists()); //Renamed over the checkpoint
+        assert_eq!(resumed.generation(), 2);
+        assert_eq!(resumed.population().len(), 30);
+    }
+
+    #[test]
+    fn hooks_are_called_in_order() {
+        #[derive(Default)]
+        struct Recorder {
+            events: Vec<String>,
+        }
+        impl TrainerHooks for Recorder {
+            fn on_generation_start(&mut self, trainer: &Trainer) -> Result<(), Error> {
+                self.events.push(format!("start {}", trainer.generation()));
+                Ok(())
+            }
+            fn on_evaluated(&mut self, trainer: &Trainer) -> Result<(), Error> {
+                assert_eq!(trainer.losses().len(), 30);
+                self.events.push(String::from("evaluated"));
+                Ok(())
+            }
+            fn on_new_best(&mut self, _trainer: &Trainer, _network: &Network, loss: f64) -> Result<(), Error> {
+                self.events.push(format!("best {}", loss));
+                Ok(())
+            }
+            fn on_finish(&mut self, trainer: &Trainer) -> Result<(), Error> {
+                self.events.push(format!("finish {}", trainer.generation()));
+                Ok(())
+            }
+        }
+
+        let mut recorder = Recorder::default();
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 3);
+        trainer.train_with_hooks(2, |_, _| 1.0, &mut recorder).unwrap();
+        assert_eq!(recorder.events, vec!["start 0", "evaluated", "best 1", "start 1", "evaluated", "finish 2"]);
+    }
+
+    #[test]
+    fn map_elites_fills_and_resumes_archive() {
+        use crate::core_models::map_elites::{family_fraction, BehaviorDimension};
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut evaluate = |network: &mut Network, rng: &mut Rng| {
+            let loss = fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY);
+            (loss, vec![network.node_count() as f64, family_fraction(network, 200)])
+        };
+        let dimensions = vec![
+            BehaviorDimension { name: String::from("nodes"), minimum: 0.0, maximum: 20.0, bins: 10 },
+            BehaviorDimension { name: String::from("arithmetic"), minimum: 0.0, maximum: 1.0, bins: 2 },
+        ];
+
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 4);
+        let mut archive = Archive::new(dimensions);
+        for _ in 0..5 {
+            trainer.step_map_elites(&mut archive, &mut evaluate);
+        }
+        assert!(archive.len() > 1);
+        let best_loss = archive.best().unwrap().loss;
+
+        let mut resumed = Archive::from_text(&archive.to_text()).unwrap();
+        trainer.step_map_elites(&mut resumed, &mut evaluate);
+        assert!(resumed.len() >= archive.len());
+        assert!(resumed.best().unwrap().loss <= best_loss);
+    }
+
+    #[test]
+    fn map_elites_checkpoints_resume_identically() {
+        use crate::core_models::map_elites::BehaviorDimension;
+        let path = std::env::temp_dir().join(format!("steel_ml_map_elites_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let evaluate = |network: &mut Network, _: &mut Rng| (network.node_count() as f64, vec![network.complexity().connection_count as f64]);
+        let dimensions = vec![BehaviorDimension { name: String::from("connections"), minimum: 0.0, maximum: 20.0, bins: 10 }];
+
+        let mut uninterrupted = arithmetic_trainer(Selection::Fitness, 8);
+        let mut uninterrupted_archive = Archive::new(dimensions.clone());
+        uninterrupted.train_map_elites(5, &mut uninterrupted_archive, evaluate).unwrap();
+
+        let mut interrupted = arithmetic_trainer(Selection::Fitness, 8);
+        interrupted.settings.checkpoint = Some(CheckpointSettings { path: path.clone(), interval: 3 });
+        interrupted.train_map_elites(4, &mut Archive::new(dimensions), evaluate).unwrap();
+        let mut resumed = Trainer::load_checkpoint(interrupted.settings().clone(), &path).unwrap();
+        let mut resumed_archive = Archive::load(&format!("{}.archive", path)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(format!("{}.archive", path)).unwrap();
+        assert_eq!(resumed.generation(), 3);
+        resumed.train_map_elites(2, &mut resumed_archive, evaluate).unwrap();
+        assert_eq!(resumed_archive.to_text(), uninterrupted_archive.to_text());
+    }
+
+    #[test]
+    fn novelty_search_builds_archive() {
+        use crate::core_models::novelty::output_behavior;
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3\n2,2,4\n5,1,6\n3,7,10\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 2);
+        trainer.settings.novelty = NoveltySettings { novelty_weight: 0.5, archive_threshold: 5.0, ..NoveltySettings::default() };
+        let mut evaluate = |network: &mut Network, rng: &mut Rng| {
+            (fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY), output_behavior(network, &dataset, rng))
+        };
+        let path = std::env::temp_dir().join(format!("steel_ml_novelty_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        trainer.settings.checkpoint = Some(CheckpointSettings { path: path.clone(), interval: 5 });
+        trainer.train_novelty(5, &mut evaluate).unwrap();
+        assert_eq!(trainer.generation(), 5);
+        let resumed = Trainer::load_checkpoint(trainer.settings().clone(), &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.novelty_archive(), trainer.novelty_archive());
+        assert!(!trainer.novelty_archive().is_empty());
+        assert!(trainer.novelty_archive().iter().all(|behavior| behavior.len() == 4));
+        assert!(trainer.best().is_some());
+    }
+
+    #[test]
+    fn lamarckian_refinement_improves_best() {
+        use crate::core_models::constant_optimization::{ConstantOptimizer, Method};
+        let dataset = Dataset::from_csv_str("a,b,y\n1,2,3.5\n2,2,4.5\n5,1,6.5\n3,7,10.5\n").unwrap();
+        let fitness = Fitness::new(Metric::MeanSquaredError);
+        let mut score = |network: &mut Network, rng: &mut Rng| fitness.evaluate(network, &dataset, rng).unwrap_or(f64::INFINITY);
+
+        let mut plain = arithmetic_trainer(Selection::Fitness, 6);
+        let mut refined = arithmetic_trainer(Selection::Fitness, 6);
+        refined.settings.refinement = Some(Refinement {
+            optimizer: ConstantOptimizer { evaluations: 100, ..ConstantOptimizer::new(Method::NelderMead) },
+            network_count: 3,
+        });
+        for _ in 0..5 {
+            plain.step(&mut score);
+            refined.step(&mut score);
+        }
+        assert!(refined.best().unwrap().1 <= plain.best().unwrap().1);
+        let (best_network, best_loss) = refined.best().unwrap();
+        assert_eq!(score(&mut best_network.clone(), &mut Rng::new(0)), best_loss);
+    }
+
+    #[test]
+    fn selection_modes_order_networks() {
+        let small = Complexity { node_count: 2, connection_count: 2, depth: 1, effective_node_count: 2, family_histogram: Default::default() };
+        let large = Complexity { node_count: 9, connection_count: 12, depth: 4, effective_node_count: 7, family_histogram: Default::default() };
+        assert_eq!(compare_networks(&Selection::Fitness, 1.0, &small, 0.99, &large), Ordering::Greater);
+        assert_eq!(compare_networks(&Selection::Lexicographic(0.05), 1.0, &small, 0.99, &large), Ordering::Less);
+        assert_eq!(compare_networks(&Selection::Lexicographic(0.001), 1.0, &small, 0.99, &large), Ordering::Greater);
+        let weights = ComplexityWeights { node: 0.01, connection: 0.0, depth: 0.0, effective_node: 0.0 };
+        assert_eq!(compare_networks(&Selection::Penalized(weights), 1.0, &small, 0.99, &large), Ordering::Less);
+    }
+
+    #[test]
+    fn rewire_keeps_other_connections_in_place() {
+        let mut trainer = arithmetic_trainer(Selection::Fitness, 5);
+        let mut network = Network::new();
+        let _ = network.generate_layer_to_network();
+        let _ = network.generate_node_to_layer(202, 0);
+        for value in [1.0, 2.0, 3.0, 4.0] {
+            network.connect_constant(0, 0, value).unwrap();
+        }
+        for _ in 0..20 {
+            let before: Vec<String> = network.layers[0].nodes[0].connections.iter().map(|connection| format!("{:?}", connection)).collect();
+            trainer.rewire(&mut network, 0, 0);
+            let after: Vec<String> = network.layers[0].nodes[0].connections.iter().map(|connection| format!("{:?}", connection)).collect();
+            assert_eq!(after.len(), 4);
+            assert!(before.iter().zip(&after).filter(|(before, after)| before != after).count() <= 1);
+        }
+    }
+
+    #[test]
+    fn lexicographic_ranking_of_a_large_population() {
+        let mut rng = Rng::new(3);
+        for _ in 0..50 {
+            let losses: Vec<f64> = (0..150).map(|index| if index % 37 == 0 { f64::INFINITY } else { rng.next_f64() }).collect();
+            let complexities: Vec<Complexity> = (0..150).map(|_| {
+                let node_count = rng.range(1, 20);
+                Complexity { node_count, connection_count: node_count * 2, depth: 1, effective_node_count: node_count, family_histogram: Default::default() }
+            }).collect();
+            let ranking = rank_networks(&Selection::Lexicographic(0.1), &losses, &complexities);
+
+            let mut sorted = ranking.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..150).collect::<Vec<usize>>());
+            let best_loss = losses.iter().copied().fold(f64::INFINITY, f64::min);
+            assert!(losses[ranking[0]] - best_loss <= 0.1); //Never more than the tolerance worse than the best
+            assert!(ranking.windows(2).all(|pair| losses[pair[1]] >= losses[pair[0]] - 0.1));
+            assert!(losses[*ranking.last().unwrap()].is_infinite());
+        }
+    }
+}
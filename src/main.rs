@@ -0,0 +1,297 @@
+use std::collections::*;
+use std::process::ExitCode;
+use steel_ml::core_models::config::{Mode, Objective, TrainingConfig};
+use steel_ml::core_models::dannn::{Error, Network, NodeSelectionTable, Value};
+use steel_ml::core_models::dataset::Dataset;
+use steel_ml::core_models::export;
+use steel_ml::core_models::fitness::Fitness;
+use steel_ml::core_models::metrics::MetricsLog;
+use steel_ml::core_models::novelty;
+use steel_ml::core_models::random::Rng;
+use steel_ml::core_models::serialization;
+use steel_ml::core_models::trainer::{CheckpointSettings, Trainer, TrainerHooks, TrainerSettings};
+
+/*
+The steel_ml command line tool. Models are networks saved in the text format of the serialization module, datasets
+are CSV files (see the dataset module), and training configs are TOML files (see the config module).
+ */
+
+const USAGE: &str = "usage:
+    steel_ml train <dataset.csv> <config> [--output model.txt] [--metrics metrics.jsonl] [--checkpoint path] [--resume path]
+    steel_ml eval <model> <data.csv> [--targets a,b] [--output predictions.csv]
+    steel_ml inspect <model>
+    steel_ml export <model> <dot|expression|rust> [--inputs a,b] [--name function_name] [--output path]
+    steel_ml validate <model> [--inputs count] [--outputs count]
+    steel_ml validate <config.toml>";
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let result = match arguments.first().map(String::as_str) {
+        Some("train") => train(&arguments[1..]),
+        Some("eval") => eval(&arguments[1..]),
+        Some("inspect") => inspect(&arguments[1..]),
+        Some("export") => export_model(&arguments[1..]),
+        Some("validate") => validate(&arguments[1..]),
+        _ => Err(String::from(USAGE)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+struct Arguments {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+fn parse_arguments(arguments: &[String], positional_count: usize, option_names: &[&str]) -> Result<Arguments, String> {
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        match argument.strip_prefix("--") {
+            Some(name) if option_names.contains(&name) => match arguments.next() {
+                Some(value) => {
+                    options.insert(String::from(name), value.clone());
+                }
+                None => return Err(format!("--{} needs a value\n{}", name, USAGE)),
+            },
+            Some(name) => return Err(format!("unknown option --{}\n{}", name, USAGE)),
+            None => positional.push(argument.clone()),
+        }
+    }
+    if positional.len() != positional_count {
+        return Err(String::from(USAGE));
+    }
+    Ok(Arguments { positional, options })
+} //Every option takes a value, the amount of positional arguments is fixed per subcommand
+
+fn describe(error: Error, context: &str) -> String {
+    format!("{}: {:?}", context, error)
+}
+
+fn load_model(path: &str) -> Result<Network, String> {
+    serialization::load_network(path).map_err(|error| describe(error, &format!("could not load model {}", path)))
+}
+
+fn write_output(text: &str, path: Option<&String>) -> Result<(), String> {
+    match path {
+        Some(path) => std::fs::write(path, text).map_err(|_| format!("could not write {}", path)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+} //Writes to the --output file if there is one, otherwise to standard output
+
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect()
+}
+
+struct CommandLineHooks {
+    metrics: Option<MetricsLog<std::fs::File>>,
+}
+
+impl TrainerHooks for CommandLineHooks {
+    fn on_evaluated(&mut self, trainer: &Trainer) -> Result<(), Error> {
+        match &mut self.metrics {
+            Some(metrics) => metrics.on_evaluated(trainer),
+            None => Ok(()),
+        }
+    }
+
+    fn on_new_best(&mut self, trainer: &Trainer, network: &Network, loss: f64) -> Result<(), Error> {
+        eprintln!("generation {}: loss {} with {} nodes", trainer.generation(), loss, network.node_count());
+        Ok(())
+    }
+
+    fn on_finish(&mut self, trainer: &Trainer) -> Result<(), Error> {
+        match &mut self.metrics {
+            Some(metrics) => metrics.on_finish(trainer),
+            None => Ok(()),
+        }
+    }
+} //Reports every new best network and writes the metrics log when asked to
+
+fn train(arguments: &[String]) -> Result<(), String> {
+    let arguments = parse_arguments(arguments, 2, &["output", "metrics", "checkpoint", "resume"])?;
+    let (dataset_path, config_path) = (&arguments.positional[0], &arguments.positional[1]);
+    let config = TrainingConfig::load(config_path).map_err(|error| format!("{}: {}", config_path, error))?;
+
+    let mut dataset = Dataset::from_csv_path(dataset_path).map_err(|error| describe(error, &format!("could not load dataset {}", dataset_path)))?;
+    if let Some(targets) = &config.targets {
+        let targets: Vec<&str> = targets.iter().map(String::as_str).collect();
+        dataset.set_targets(&targets).map_err(|error| describe(error, "training.targets"))?;
+    }
+    let seed = config.settings.seed;
+    let (train_set, validation_set, _) = dataset.split(config.validation_fraction, 0.0, seed)
+        .map_err(|error| describe(error, "training.validation_fraction"))?;
+
+    let settings = TrainerSettings {
+        input_count: dataset.feature_names().len(),
+        output_count: dataset.target_names().len(),
+        checkpoint: arguments.options.get("checkpoint").map(|path| CheckpointSettings { path: path.clone(), interval: config.checkpoint_interval }),
+        ..config.settings.clone()
+    };
+    let fitness = Fitness::new(config.metric.clone()).with_parsimony(config.parsimony);
+
+    let mut trainer = match arguments.options.get("resume") {
+        Some(path) => Trainer::load_checkpoint(settings, path).map_err(|error| describe(error, &format!("could not resume from {}", path)))?,
+        None => Trainer::new(settings, config.node_selection_table.clone()).map_err(|error| describe(error, "could not start training"))?,
+    };
+    let generations = config.generations.saturating_sub(trainer.generation());
+    let score = |network: &mut Network, rng: &mut Rng| fitness.evaluate(network, &train_set, rng).unwrap_or(f64::INFINITY);
+
+    let metrics = match arguments.options.get("metrics") {
+        Some(path) => Some(MetricsLog::create(path).map_err(|error| describe(error, &format!("could not create {}", path)))?),
+        None => None,
+    };
+    let mut hooks = CommandLineHooks { metrics };
+
+    let best = if config.objectives.len() > 1 {
+        if config.settings.speciation.is_some() || config.settings.refinement.is_some() {
+            return Err(format!("{}: speciation and refinement need a single objective", config_path));
+        }
+        let objectives = |network: &mut Network, rng: &mut Rng| {
+            let loss = score(network, rng);
+            let complexity = network.complexity();
+            config.objectives.iter().map(|objective| objective.measure(loss, &complexity)).collect::<Vec<f64>>()
+        };
+        let front = trainer.train_multi_objective_with_hooks(generations, objectives, &mut hooks).map_err(|error| describe(error, "training stopped"))?;
+        eprintln!("pareto front of {} networks", front.len());
+        let loss_index = config.objectives.iter().position(|objective| *objective == Objective::Loss).unwrap_or(0);
+        front.into_iter()
+            .min_by(|(_, first), (_, second)| first[loss_index].total_cmp(&second[loss_index]))
+            .map(|(network, _)| network)
+            .ok_or(String::from("training produced no network"))?
+    } else if config.mode == Mode::Novelty {
+        let evaluate = |network: &mut Network, rng: &mut Rng| (score(network, rng), novelty::output_behavior(network, &train_set, rng));
+        trainer.train_novelty_with_hooks(generations, evaluate, &mut hooks)
+            .map_err(|error| describe(error, "training stopped"))?
+            .ok_or(String::from("training produced no network"))?
+    } else {
+        trainer.train_with_hooks(generations, score, &mut hooks)
+            .map_err(|error| describe(error, "training stopped"))?
+            .ok_or(String::from("training produced no network"))?
+    };
+    let output_path = arguments.options.get("output").cloned().unwrap_or(String::from("model.txt"));
+    serialization::save_network(&best, &output_path).map_err(|error| describe(error, &format!("could not save {}", output_path)))?;
+    println!("training loss: {}", score(&mut best.clone(), &mut Rng::new(seed)));
+    if !validation_set.is_empty() {
+        let validation_loss = fitness.evaluate(&mut best.clone(), &validation_set, &mut Rng::new(seed)).unwrap_or(f64::INFINITY);
+        println!("validation loss: {}", validation_loss);
+    }
+    println!("saved {}", output_path);
+    Ok(())
+} //Trains on a dataset with a config, saves the best network
+
+fn csv_field(value: &Value) -> String {
+    let text = value.as_text();
+    if text.contains([',', '"', '\n']) {
+        return format!("\"{}\"", text.replace('"', "\"\""));
+    }
+    text
+}
+
+fn eval(arguments: &[String]) -> Result<(), String> {
+    let arguments = parse_arguments(arguments, 2, &["targets", "output"])?;
+    let mut network = load_model(&arguments.positional[0])?;
+    let data_path = &arguments.positional[1];
+    let mut dataset = Dataset::from_csv_path(data_path).map_err(|error| describe(error, &format!("could not load {}", data_path)))?;
+    let targets = split_list(arguments.options.get("targets").map(String::as_str).unwrap_or(""));
+    let targets: Vec<&str> = targets.iter().map(String::as_str).collect();
+    dataset.set_targets(&targets).map_err(|error| describe(error, "--targets"))?; //Every column that isn't a target is an input
+
+    let outputs = dataset.evaluate(&mut network, &mut Rng::new(0)).map_err(|error| describe(error, "could not evaluate the model"))?;
+    let mut text = (0..network.output_count()).map(|output_index| format!("output_{}", output_index)).collect::<Vec<String>>().join(",");
+    text.push('\n');
+    for row in outputs {
+        text.push_str(&row.iter().map(csv_field).collect::<Vec<String>>().join(","));
+        text.push('\n');
+    }
+    write_output(&text, arguments.options.get("output"))
+} //Writes one CSV row of predictions per row of the data
+
+fn inspect(arguments: &[String]) -> Result<(), String> {
+    let arguments = parse_arguments(arguments, 1, &[])?;
+    let network = load_model(&arguments.positional[0])?;
+    let complexity = network.complexity();
+    let join = |values: Vec<String>| values.join(", ");
+
+    println!("layers: {}", network.layer_sizes().len());
+    println!("nodes per layer: {}", join(network.layer_sizes().iter().map(usize::to_string).collect()));
+    println!("outputs: {}", network.output_count());
+    println!("inputs used: {}", join(network.used_inputs().iter().map(usize::to_string).collect()));
+    println!("constants: {}", network.constants().len());
+    println!("node count: {}", complexity.node_count);
+    println!("effective node count: {}", complexity.effective_node_count);
+    println!("connection count: {}", complexity.connection_count);
+    println!("depth: {}", complexity.depth);
+    println!("node types:");
+    for (node_type, count) in network.node_type_counts() {
+        let name = NodeSelectionTable::node_type_name(node_type).unwrap_or(String::from("unknown"));
+        println!("    {} {}: {}", node_type, name, count);
+    }
+    Ok(())
+} //Prints a summary of a model, its complexity and how often each node type is used
+
+fn export_model(arguments: &[String]) -> Result<(), String> {
+    let arguments = parse_arguments(arguments, 2, &["inputs", "name", "output"])?;
+    let network = load_model(&arguments.positional[0])?;
+    let input_names = split_list(arguments.options.get("inputs").map(String::as_str).unwrap_or(""));
+    let text = match arguments.positional[1].as_str() {
+        "dot" => export::to_dot(&network, &input_names),
+        "expression" => export::to_expressions(&network, &input_names).iter().map(|expression| format!("{}\n", expression)).collect(),
+        "rust" => export::to_rust(&network, arguments.options.get("name").map(String::as_str).unwrap_or("network")),
+        format => return Err(format!("unknown export format {}, expected dot, expression or rust", format)),
+    };
+    write_output(&text, arguments.options.get("output"))
+}
+
+fn validate(arguments: &[String]) -> Result<(), String> {
+    let arguments = parse_arguments(arguments, 1, &["inputs", "outputs"])?;
+    if arguments.positional[0].ends_with(".toml") {
+        TrainingConfig::load(&arguments.positional[0]).map_err(|error| error.to_string())?;
+        println!("valid");
+        return Ok(());
+    }
+    let network = load_model(&arguments.positional[0])?;
+    let count_option = |name: &str| -> Result<Option<usize>, String> {
+        match arguments.options.get(name) {
+            Some(value) => value.parse().map(Some).map_err(|_| format!("--{} must be a whole number", name)),
+            None => Ok(None),
+        }
+    };
+
+    let mut problems = network.validate(count_option("inputs")?);
+    if count_option("outputs")?.is_some_and(|output_count| output_count != network.output_count()) {
+        problems.push(format!("the model has {} outputs", network.output_count()));
+    }
+    if problems.is_empty() {
+        println!("valid");
+        return Ok(());
+    }
+    Err(problems.join("\n"))
+} //Checks that a model loads and can be evaluated, and optionally that its inputs and outputs fit a dataset, or checks a training config
+
+
+//Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argument_parsing() {
+        let arguments: Vec<String> = ["model.txt", "--inputs", "a,b", "dot"].iter().map(|argument| argument.to_string()).collect();
+        let parsed = parse_arguments(&arguments, 2, &["inputs"]).unwrap();
+        assert_eq!(parsed.positional, vec!["model.txt", "dot"]);
+        assert_eq!(split_list(&parsed.options["inputs"]), vec!["a", "b"]);
+        assert!(parse_arguments(&arguments, 2, &[]).is_err());
+        assert!(parse_arguments(&arguments[..1], 2, &["inputs"]).is_err());
+        assert_eq!(csv_field(&Value::Text(String::from("a,\"b\""))), "\"a,\"\"b\"\"\"");
+    }
+}